@@ -34,9 +34,11 @@ impl Default for BuildConfiguration {
             keep_inline_functions: true,
             feature_vulkan: cfg!(feature = "vulkan"),
             feature_svg: cfg!(feature = "svg"),
-            feature_animation: false,
+            feature_webp: cfg!(feature = "webp"),
+            feature_animation: cfg!(feature = "animation"),
             feature_dng: false,
-            feature_particles: false,
+            feature_particles: cfg!(feature = "particles"),
+            feature_shaper: cfg!(feature = "shaper"),
             all_skia_libs,
         }
     }
@@ -61,15 +63,21 @@ pub struct BuildConfiguration {
     /// Build with SVG support?
     feature_svg: bool,
 
-    /// Build with animation support (yet unsupported, no wrappers).
+    /// Build with WebP decoding and encoding support?
+    feature_webp: bool,
+
+    /// Build with animation support (the Skottie module).
     feature_animation: bool,
 
     /// Support DNG file format (currently unsupported because of build errors).
     feature_dng: bool,
 
-    /// Build the particles module (unsupported, no wrappers).
+    /// Build with particle system support (the particles module).
     feature_particles: bool,
 
+    /// Build with text shaping support (the skshaper module with ICU and HarfBuzz).
+    feature_shaper: bool,
+
     /// As of M74, There is a bug in the Skia macOS build
     /// that requires all libraries to be built, otherwise the build would fail.
     all_skia_libs: bool,
@@ -84,6 +92,10 @@ pub struct FinalBuildConfiguration {
     /// The preprocessor defines that are used for creating the bindings and
     /// building the skia-bindings library.
     pub defines: Vec<String>,
+
+    /// The Skia modules the bindings use, their include directories are added
+    /// when the bindings are created.
+    pub modules: Vec<String>,
 }
 
 impl FinalBuildConfiguration {
@@ -110,14 +122,28 @@ impl FinalBuildConfiguration {
                     if build.feature_svg { yes() } else { no() },
                 ),
                 ("skia_use_system_expat", no()),
-                ("skia_use_icu", no()),
+                (
+                    "skia_use_icu",
+                    if build.feature_shaper { yes() } else { no() },
+                ),
+                ("skia_use_system_icu", no()),
+                (
+                    "skia_use_harfbuzz",
+                    if build.feature_shaper { yes() } else { no() },
+                ),
+                ("skia_use_system_harfbuzz", no()),
                 ("skia_use_system_libjpeg_turbo", no()),
                 ("skia_use_system_libpng", no()),
-                ("skia_use_libwebp", no()),
+                (
+                    "skia_use_libwebp",
+                    if build.feature_webp { yes() } else { no() },
+                ),
+                ("skia_use_system_libwebp", no()),
                 ("skia_use_system_zlib", no()),
                 (
                     "skia_enable_skottie",
-                    if build.feature_animation || build.all_skia_libs {
+                    // skshaper is only built if a module depends on it.
+                    if build.feature_animation || build.feature_shaper || build.all_skia_libs {
                         yes()
                     } else {
                         no()
@@ -197,13 +223,41 @@ impl FinalBuildConfiguration {
             if build.feature_svg {
                 defines.push("SK_XML");
             }
+            if build.feature_animation {
+                defines.push("SK_ENABLE_SKOTTIE");
+            }
+            if build.feature_particles {
+                defines.push("SK_ENABLE_PARTICLES");
+            }
+            if build.feature_shaper {
+                defines.push("SK_ENABLE_SHAPER");
+                defines.push("SK_SHAPER_HARFBUZZ_AVAILABLE");
+            }
             if build.skia_release {
                 defines.push("NDEBUG");
             }
             defines.iter().map(|d| d.to_string()).collect()
         };
 
-        FinalBuildConfiguration { gn_args, defines }
+        let modules = {
+            let mut modules = Vec::new();
+            if build.feature_animation {
+                modules.push("skottie");
+            }
+            if build.feature_particles {
+                modules.push("particles");
+            }
+            if build.feature_shaper {
+                modules.push("skshaper");
+            }
+            modules.iter().map(|m| m.to_string()).collect()
+        };
+
+        FinalBuildConfiguration {
+            gn_args,
+            defines,
+            modules,
+        }
     }
 }
 
@@ -218,6 +272,9 @@ pub struct BinariesConfiguration {
 
     /// The TARGET specific link libraries we need to inform cargo about.
     pub link_libraries: Vec<String>,
+
+    /// The static Skia libraries we build and need to link with.
+    pub skia_libraries: Vec<String>,
 }
 
 impl BinariesConfiguration {
@@ -231,6 +288,30 @@ impl BinariesConfiguration {
         if build.feature_svg {
             features.push("svg")
         }
+        if build.feature_webp {
+            features.push("webp")
+        }
+        if build.feature_animation {
+            features.push("animation")
+        }
+        if build.feature_particles {
+            features.push("particles")
+        }
+        if build.feature_shaper {
+            features.push("shaper")
+        }
+
+        let mut skia_libraries = vec!["skia"];
+        if build.feature_animation {
+            // Skottie depends on the scene graph and the text shaper modules.
+            skia_libraries.extend(vec!["skottie", "sksg", "skshaper"]);
+        }
+        if build.feature_particles {
+            skia_libraries.push("particles");
+        }
+        if build.feature_shaper && !skia_libraries.contains(&"skshaper") {
+            skia_libraries.push("skshaper");
+        }
 
         let mut link_libraries = Vec::new();
 
@@ -263,6 +344,7 @@ impl BinariesConfiguration {
             features: features.iter().map(|f| f.to_string()).collect(),
             output_directory,
             link_libraries: link_libraries.iter().map(|lib| lib.to_string()).collect(),
+            skia_libraries: skia_libraries.iter().map(|lib| lib.to_string()).collect(),
         }
     }
 
@@ -274,7 +356,9 @@ impl BinariesConfiguration {
             "cargo:rustc-link-search={}",
             self.output_directory.to_str().unwrap()
         );
-        cargo::add_link_lib("static=skia");
+        for lib in &self.skia_libraries {
+            cargo::add_link_lib(&format!("static={}", lib));
+        }
         cargo::add_link_lib(&format!("static={}", BINDINGS_LIB_NAME));
     }
 }
@@ -379,6 +463,11 @@ fn bindgen_gen(build: &FinalBuildConfiguration, current_dir: &Path, output_direc
         // Types for which the binding generator pulls in stuff that can not be compiled.
         .opaque_type("SkDeferredDisplayList")
         .opaque_type("SkDeferredDisplayList_PendingPathsMap")
+        // Private type that is only accessed through pointers.
+        .opaque_type("SkTextBlobRunIterator")
+        // codec/
+        .whitelist_type("SkAndroidCodec")
+        .whitelist_type("SkCodec")
         // core/
         .whitelist_type("SkAutoCanvasRestore")
         .whitelist_type("SkColorSpacePrimaries")
@@ -408,6 +497,9 @@ fn bindgen_gen(build: &FinalBuildConfiguration, current_dir: &Path, output_direc
         .whitelist_type("SkLayerDrawLooper_Bits")
         .whitelist_type("SkPerlinNoiseShader")
         .whitelist_type("SkTableColorFilter")
+        // encode/
+        .whitelist_type("SkJpegEncoder")
+        .whitelist_type("SkWebpEncoder")
         // gpu/
         .whitelist_type("GrGLBackendState")
         // gpu/vk/
@@ -467,6 +559,28 @@ fn bindgen_gen(build: &FinalBuildConfiguration, current_dir: &Path, output_direc
         cc_build.include(include_path);
     }
 
+    {
+        // SkTextBlobPriv.h
+        let include_path = current_dir.join(Path::new("skia/src/core"));
+        builder = builder.clang_arg(format!("-I{}", include_path.display()));
+        cc_build.include(include_path);
+    }
+
+    for module in &build.modules {
+        let include_path = current_dir.join(Path::new("skia/modules").join(module).join("include"));
+        cargo::add_dependent_path(include_path.to_str().unwrap());
+        builder = builder.clang_arg(format!("-I{}", include_path.display()));
+        cc_build.include(include_path);
+    }
+
+    if build.modules.iter().any(|m| m == "particles") {
+        // SkParticleSerialization.h includes SkJSON.h, which includes SkArenaAlloc.h
+        // (skia/src/core is always included).
+        let include_path = current_dir.join(Path::new("skia/src/utils"));
+        builder = builder.clang_arg(format!("-I{}", include_path.display()));
+        cc_build.include(include_path);
+    }
+
     for define in &build.defines {
         cc_build.define(&define, "1");
         builder = builder.clang_arg(format!("-D{}=1", define));
@@ -1,7 +1,11 @@
 // codec/
+#include "SkAndroidCodec.h"
+#include "SkCodec.h"
+#include "SkCodecAnimation.h"
 #include "SkEncodedOrigin.h"
 // core/
 #include "SkAnnotation.h"
+#include "SkBBHFactory.h"
 #include "SkCanvas.h"
 #include "SkColor.h"
 #include "SkColorFilter.h"
@@ -36,6 +40,7 @@
 #include "SkRegion.h"
 #include "SkRRect.h"
 #include "SkRSXform.h"
+#include "SkSerialProcs.h"
 #include "SkStream.h"
 #include "SkStrokeRec.h"
 #include "SkSurface.h"
@@ -77,6 +82,10 @@
 #include "SkTableColorFilter.h"
 #include "SkTileImageFilter.h"
 #include "SkXfermodeImageFilter.h"
+// encode/
+#include "SkJpegEncoder.h"
+#include "SkPngEncoder.h"
+#include "SkWebpEncoder.h"
 // gpu/
 #include "GrContext.h"
 #include "GrBackendDrawableInfo.h"
@@ -88,11 +97,19 @@
 #include "Sk3D.h"
 #include "SkCamera.h"
 #include "SkInterpolator.h"
+#include "SkNoDrawCanvas.h"
 #include "SkNullCanvas.h"
+#include "SkPaintFilterCanvas.h"
 #include "SkParsePath.h"
 #include "SkShadowUtils.h"
 #include "SkTextUtils.h"
 
+// src/core/
+#include "SkTextBlobPriv.h"
+
+#include <cstring>
+#include <vector>
+
 #if defined(SK_VULKAN)
 #include "vk/GrVkVulkan.h"
 #include "vk/GrVkTypes.h"
@@ -104,11 +121,146 @@
 #include "SkSVGCanvas.h"
 #endif
 
+#if defined(SK_ENABLE_SKOTTIE)
+#include "Skottie.h"
+#endif
+
+#if defined(SK_ENABLE_SHAPER)
+#include "SkShaper.h"
+#endif
+
+#if defined(SK_ENABLE_PARTICLES)
+#include "SkOnce.h"
+#include "SkParticleEffect.h"
+#include "SkParticleSerialization.h"
+#include "SkRandom.h"
+#endif
+
 template<typename T>
 inline sk_sp<T> spFromConst(const T* pt) {
     return sk_sp<T>(const_cast<T*>(pt));
 }
 
+//
+// core/SkSerialProcs.h
+//
+// The Rust procs return pointers with a reference count owned by the caller, or null to
+// use the default serialization.
+//
+
+struct RustSerialProcs {
+    void* data;
+    SkData* (*picture)(void* data, SkPicture* picture);
+    SkData* (*image)(void* data, SkImage* image);
+    SkData* (*typeface)(void* data, SkTypeface* typeface);
+};
+
+struct RustDeserialProcs {
+    void* data;
+    SkPicture* (*picture)(void* data, const void* bytes, size_t length);
+    SkImage* (*image)(void* data, const void* bytes, size_t length);
+    SkTypeface* (*typeface)(void* data, const void* bytes, size_t length);
+};
+
+namespace {
+    SkSerialProcs makeSerialProcs(const RustSerialProcs* rust) {
+        SkSerialProcs procs;
+        if (!rust) {
+            return procs;
+        }
+        auto context = const_cast<RustSerialProcs*>(rust);
+        if (rust->picture) {
+            procs.fPictureProc = [](SkPicture* picture, void* ctx) {
+                auto rust = static_cast<RustSerialProcs*>(ctx);
+                return sk_sp<SkData>(rust->picture(rust->data, picture));
+            };
+            procs.fPictureCtx = context;
+        }
+        if (rust->image) {
+            procs.fImageProc = [](SkImage* image, void* ctx) {
+                auto rust = static_cast<RustSerialProcs*>(ctx);
+                return sk_sp<SkData>(rust->image(rust->data, image));
+            };
+            procs.fImageCtx = context;
+        }
+        if (rust->typeface) {
+            procs.fTypefaceProc = [](SkTypeface* typeface, void* ctx) {
+                auto rust = static_cast<RustSerialProcs*>(ctx);
+                sk_sp<SkData> data(rust->typeface(rust->data, typeface));
+                if (!data || data->isEmpty()) {
+                    // Typefaces without data would be written to the typeface table of a
+                    // picture, so they are serialized here to keep that table empty.
+                    return typeface->serialize();
+                }
+                return data;
+            };
+            procs.fTypefaceCtx = context;
+        }
+        return procs;
+    }
+
+    sk_sp<SkTypeface> deserializeTypeface(const RustDeserialProcs* rust, const void* bytes, size_t length) {
+        sk_sp<SkTypeface> typeface(rust->typeface(rust->data, bytes, length));
+        if (!typeface) {
+            SkMemoryStream stream(bytes, length, false);
+            typeface = SkTypeface::MakeDeserialize(&stream);
+        }
+        return typeface;
+    }
+
+    SkDeserialProcs makeDeserialProcs(const RustDeserialProcs* rust) {
+        SkDeserialProcs procs;
+        if (!rust) {
+            return procs;
+        }
+        auto context = const_cast<RustDeserialProcs*>(rust);
+        if (rust->picture) {
+            procs.fPictureProc = [](const void* bytes, size_t length, void* ctx) {
+                auto rust = static_cast<RustDeserialProcs*>(ctx);
+                return sk_sp<SkPicture>(rust->picture(rust->data, bytes, length));
+            };
+            procs.fPictureCtx = context;
+        }
+        if (rust->image) {
+            procs.fImageProc = [](const void* bytes, size_t length, void* ctx) {
+                auto rust = static_cast<RustDeserialProcs*>(ctx);
+                return sk_sp<SkImage>(rust->image(rust->data, bytes, length));
+            };
+            procs.fImageCtx = context;
+        }
+        if (rust->typeface) {
+            procs.fTypefaceProc = [](const void* bytes, size_t length, void* ctx) {
+                return deserializeTypeface(static_cast<RustDeserialProcs*>(ctx), bytes, length);
+            };
+            procs.fTypefaceCtx = context;
+        }
+        return procs;
+    }
+
+    // Pictures that were not serialized with a typeface proc may contain a typeface table.
+    // SkPictureData reads its entries by invoking the typeface proc with a pointer to the
+    // picture's stream instead of the serialized typeface, so these calls are recognized by
+    // comparing against the stream and the typefaces are deserialized by Skia.
+    SkPicture* makePictureFromStream(SkStream* stream, const RustDeserialProcs* rust) {
+        struct Context {
+            const RustDeserialProcs* rust;
+            SkStream* stream;
+        } context = { rust, stream };
+        auto procs = makeDeserialProcs(rust);
+        if (rust && rust->typeface) {
+            procs.fTypefaceProc = [](const void* bytes, size_t length, void* ctx) {
+                auto context = static_cast<Context*>(ctx);
+                if (length == sizeof(SkStream*) && *static_cast<SkStream* const*>(bytes) == context->stream) {
+                    return SkTypeface::MakeDeserialize(context->stream);
+                }
+                return deserializeTypeface(context->rust, bytes, length);
+            };
+            procs.fTypefaceCtx = &context;
+        }
+        return SkPicture::MakeFromStream(stream, &procs).release();
+    }
+}
+
 //
 // codec/SkEncodedOrigin.h
 //
@@ -117,6 +269,167 @@ extern "C" void C_SkEncodedOriginToMatrix(SkEncodedOrigin origin, int w, int h,
     *matrix = SkEncodedOriginToMatrix(origin, w, h);
 }
 
+//
+// codec/SkCodec.h
+//
+
+extern "C" void C_SkCodec_delete(SkCodec* self) {
+    delete self;
+}
+
+extern "C" SkCodec* C_SkCodec_MakeFromData(const SkData* data) {
+    return SkCodec::MakeFromData(spFromConst(data)).release();
+}
+
+// note: this function _consumes_ / deletes the stream.
+extern "C" SkCodec* C_SkCodec_MakeFromStream(SkStream* stream) {
+    return SkCodec::MakeFromStream(std::unique_ptr<SkStream>(stream)).release();
+}
+
+extern "C" void C_SkCodec_getInfo(const SkCodec* self, SkImageInfo* info) {
+    *info = self->getInfo();
+}
+
+extern "C" void C_SkCodec_dimensions(const SkCodec* self, SkISize* dimensions) {
+    *dimensions = self->dimensions();
+}
+
+extern "C" void C_SkCodec_bounds(const SkCodec* self, SkIRect* bounds) {
+    *bounds = self->bounds();
+}
+
+extern "C" SkEncodedOrigin C_SkCodec_getOrigin(const SkCodec* self) {
+    return self->getOrigin();
+}
+
+extern "C" void C_SkCodec_getScaledDimensions(const SkCodec* self, float desiredScale, SkISize* dimensions) {
+    *dimensions = self->getScaledDimensions(desiredScale);
+}
+
+extern "C" bool C_SkCodec_getValidSubset(const SkCodec* self, SkIRect* desiredSubset) {
+    return self->getValidSubset(desiredSubset);
+}
+
+extern "C" SkEncodedImageFormat C_SkCodec_getEncodedFormat(const SkCodec* self) {
+    return self->getEncodedFormat();
+}
+
+extern "C" SkCodec::Result C_SkCodec_getPixels(SkCodec* self, const SkImageInfo* info, void* pixels, size_t rowBytes, const SkCodec::Options* options) {
+    return self->getPixels(*info, pixels, rowBytes, options);
+}
+
+extern "C" SkCodec::Result C_SkCodec_startIncrementalDecode(SkCodec* self, const SkImageInfo* info, void* pixels, size_t rowBytes, const SkCodec::Options* options) {
+    return self->startIncrementalDecode(*info, pixels, rowBytes, options);
+}
+
+extern "C" SkCodec::Result C_SkCodec_incrementalDecode(SkCodec* self, int* rowsDecoded) {
+    return self->incrementalDecode(rowsDecoded);
+}
+
+extern "C" SkCodec::Result C_SkCodec_startScanlineDecode(SkCodec* self, const SkImageInfo* info, const SkCodec::Options* options) {
+    return self->startScanlineDecode(*info, options);
+}
+
+extern "C" int C_SkCodec_getScanlines(SkCodec* self, void* dst, int countLines, size_t rowBytes) {
+    return self->getScanlines(dst, countLines, rowBytes);
+}
+
+extern "C" bool C_SkCodec_skipScanlines(SkCodec* self, int countLines) {
+    return self->skipScanlines(countLines);
+}
+
+extern "C" SkCodec::SkScanlineOrder C_SkCodec_getScanlineOrder(const SkCodec* self) {
+    return self->getScanlineOrder();
+}
+
+extern "C" int C_SkCodec_nextScanline(const SkCodec* self) {
+    return self->nextScanline();
+}
+
+extern "C" int C_SkCodec_outputScanline(const SkCodec* self, int inputScanline) {
+    return self->outputScanline(inputScanline);
+}
+
+extern "C" int C_SkCodec_getFrameCount(SkCodec* self) {
+    return self->getFrameCount();
+}
+
+extern "C" bool C_SkCodec_getFrameInfo(const SkCodec* self, int index, SkCodec::FrameInfo* info) {
+    return self->getFrameInfo(index, info);
+}
+
+extern "C" int C_SkCodec_getRepetitionCount(SkCodec* self) {
+    return self->getRepetitionCount();
+}
+
+extern "C" const char* C_SkCodec_ResultToString(SkCodec::Result result) {
+    return SkCodec::ResultToString(result);
+}
+
+//
+// codec/SkAndroidCodec.h
+//
+
+extern "C" void C_SkAndroidCodec_delete(SkAndroidCodec* self) {
+    delete self;
+}
+
+extern "C" SkAndroidCodec* C_SkAndroidCodec_MakeFromData(const SkData* data) {
+    return SkAndroidCodec::MakeFromData(spFromConst(data)).release();
+}
+
+// note: this function _consumes_ / deletes the codec.
+extern "C" SkAndroidCodec* C_SkAndroidCodec_MakeFromCodec(SkCodec* codec) {
+    return SkAndroidCodec::MakeFromCodec(std::unique_ptr<SkCodec>(codec)).release();
+}
+
+extern "C" void C_SkAndroidCodec_getInfo(const SkAndroidCodec* self, SkImageInfo* info) {
+    *info = self->getInfo();
+}
+
+extern "C" SkEncodedImageFormat C_SkAndroidCodec_getEncodedFormat(const SkAndroidCodec* self) {
+    return self->getEncodedFormat();
+}
+
+extern "C" SkColorType C_SkAndroidCodec_computeOutputColorType(SkAndroidCodec* self, SkColorType requestedColorType) {
+    return self->computeOutputColorType(requestedColorType);
+}
+
+extern "C" SkAlphaType C_SkAndroidCodec_computeOutputAlphaType(SkAndroidCodec* self, bool requestedUnpremul) {
+    return self->computeOutputAlphaType(requestedUnpremul);
+}
+
+extern "C" SkColorSpace* C_SkAndroidCodec_computeOutputColorSpace(SkAndroidCodec* self, SkColorType outputColorType, const SkColorSpace* prefColorSpace) {
+    return self->computeOutputColorSpace(outputColorType, spFromConst(prefColorSpace)).release();
+}
+
+extern "C" int C_SkAndroidCodec_computeSampleSize(const SkAndroidCodec* self, SkISize* size) {
+    return self->computeSampleSize(size);
+}
+
+extern "C" void C_SkAndroidCodec_getSampledDimensions(const SkAndroidCodec* self, int sampleSize, SkISize* dimensions) {
+    *dimensions = self->getSampledDimensions(sampleSize);
+}
+
+extern "C" bool C_SkAndroidCodec_getSupportedSubset(const SkAndroidCodec* self, SkIRect* desiredSubset) {
+    return self->getSupportedSubset(desiredSubset);
+}
+
+extern "C" void C_SkAndroidCodec_getSampledSubsetDimensions(const SkAndroidCodec* self, int sampleSize, const SkIRect* subset, SkISize* dimensions) {
+    *dimensions = self->getSampledSubsetDimensions(sampleSize, *subset);
+}
+
+extern "C" SkCodec::Result C_SkAndroidCodec_getAndroidPixels(
+        SkAndroidCodec* self,
+        const SkImageInfo* info, void* pixels, size_t rowBytes,
+        SkCodec::ZeroInitialized zeroInitialized, const SkIRect* subset, int sampleSize) {
+    SkAndroidCodec::AndroidOptions options;
+    options.fZeroInitialized = zeroInitialized;
+    options.fSubset = const_cast<SkIRect*>(subset);
+    options.fSampleSize = sampleSize;
+    return self->getAndroidPixels(*info, pixels, rowBytes, &options);
+}
+
 //
 // SkSurface
 //
@@ -455,6 +768,14 @@ extern "C" SkData *C_SkEncodeBitmap(const SkBitmap *src, SkEncodedImageFormat fo
     return SkEncodeBitmap(*src, format, quality).release();
 }
 
+extern "C" bool C_SkEncodeImage(SkWStream* dst, const SkPixmap* src, SkEncodedImageFormat format, int quality) {
+    return SkEncodeImage(dst, *src, format, quality);
+}
+
+extern "C" bool C_SkEncodeImage2(SkWStream* dst, const SkBitmap* src, SkEncodedImageFormat format, int quality) {
+    return SkEncodeImage(dst, *src, format, quality);
+}
+
 //
 // core/SkData.h
 //
@@ -547,6 +868,15 @@ extern "C" void C_SkPaint_setDrawLooper(SkPaint* self, const SkDrawLooper* drawL
     self->setDrawLooper(spFromConst(drawLooper));
 }
 
+extern "C" bool C_SkPaint_computeFastBounds(const SkPaint* self, const SkRect* orig, SkRect* bounds) {
+    if (!self->canComputeFastBounds()) {
+        return false;
+    }
+    SkRect storage;
+    *bounds = self->computeFastBounds(*orig, &storage);
+    return true;
+}
+
 //
 // SkPath
 //
@@ -648,6 +978,10 @@ extern "C" void C_SkCanvas_discard(SkCanvas* self) {
     self->discard();
 }
 
+extern "C" void C_SkCanvas_drawAtlas(SkCanvas* self, const SkImage* atlas, const SkRSXform xform[], const SkRect tex[], const SkColor colors[], int count, SkBlendMode mode, const SkRect* cullRect, const SkPaint* paint) {
+    self->drawAtlas(atlas, xform, tex, colors, count, mode, cullRect, paint);
+}
+
 //
 // SkAutoCanvasRestore
 //
@@ -862,16 +1196,23 @@ extern "C" SkShader* C_SkBitmap_makeShader(const SkBitmap* self, SkTileMode tmx,
 // SkPicture
 //
 
-extern "C" SkPicture* C_SkPicture_MakeFromData(const SkData* data) {
-    return SkPicture::MakeFromData(data).release();
+extern "C" SkPicture* C_SkPicture_MakeFromData(const SkData* data, const RustDeserialProcs* procs) {
+    SkMemoryStream stream(data->data(), data->size());
+    return makePictureFromStream(&stream, procs);
 }
 
-extern "C" SkPicture* C_SkPicture_MakeFromData2(const void* data, size_t size) {
-    return SkPicture::MakeFromData(data, size).release();
+extern "C" SkPicture* C_SkPicture_MakeFromData2(const void* data, size_t size, const RustDeserialProcs* procs) {
+    SkMemoryStream stream(data, size);
+    return makePictureFromStream(&stream, procs);
 }
 
-extern "C" SkData* C_SkPicture_serialize(const SkPicture* self) {
-    return self->serialize().release();
+extern "C" SkPicture* C_SkPicture_MakeFromStream(SkStream* stream, const RustDeserialProcs* procs) {
+    return makePictureFromStream(stream, procs);
+}
+
+extern "C" SkData* C_SkPicture_serialize(const SkPicture* self, const RustSerialProcs* procs) {
+    auto serialProcs = makeSerialProcs(procs);
+    return self->serialize(&serialProcs).release();
 }
 
 extern "C" SkPicture* C_SkPicture_MakePlaceholder(const SkRect& cull) {
@@ -882,6 +1223,60 @@ extern "C" void C_SkPicture_playback(const SkPicture* self, SkCanvas* canvas) {
     self->playback(canvas);
 }
 
+typedef bool (*RustAbortCallback_Abort)(void* data);
+
+// An AbortCallback that forwards to a Rust closure.
+// abort() is called by the playback before each op that is played back. If the picture
+// has a bounding box hierarchy and the canvas' clip does not contain the cull rect, only
+// the ops that intersect the clip are played back.
+class RustAbortCallback : public SkPicture::AbortCallback {
+public:
+    RustAbortCallback(void* data, RustAbortCallback_Abort abort)
+        : fData(data), fAbort(abort) {}
+
+    bool abort() override {
+        return fAbort(fData);
+    }
+
+private:
+    void* fData;
+    RustAbortCallback_Abort fAbort;
+};
+
+extern "C" void C_SkPicture_playbackWithAbort(const SkPicture* self, SkCanvas* canvas, void* data, RustAbortCallback_Abort abort) {
+    RustAbortCallback callback(data, abort);
+    self->playback(canvas, &callback);
+}
+
+// A canvas that forwards all calls to another canvas, but drops the draw calls while
+// drawing is disabled. Used to play back a range of picture ops.
+// Drawables and annotations are not filtered by SkPaintFilterCanvas and are always forwarded.
+class RangePlaybackCanvas : public SkPaintFilterCanvas {
+public:
+    RangePlaybackCanvas(SkCanvas* canvas, bool draw)
+        : SkPaintFilterCanvas(canvas), fDraw(draw) {}
+
+    void setDraw(bool draw) {
+        fDraw = draw;
+    }
+
+protected:
+    bool onFilter(SkTCopyOnFirstWrite<SkPaint>*, Type) const override {
+        return fDraw;
+    }
+
+private:
+    bool fDraw;
+};
+
+extern "C" SkCanvas* C_RangePlaybackCanvas_new(SkCanvas* canvas, bool draw) {
+    return new RangePlaybackCanvas(canvas, draw);
+}
+
+extern "C" void C_RangePlaybackCanvas_setDraw(SkCanvas* self, bool draw) {
+    static_cast<RangePlaybackCanvas*>(self)->setDraw(draw);
+}
+
 extern "C" SkRect C_SkPicture_cullRect(const SkPicture* self) {
     return self->cullRect();
 }
@@ -979,6 +1374,84 @@ extern "C" SkTextBlob* C_SkTextBlob_MakeFromText(const void* text, size_t byteLe
     return SkTextBlob::MakeFromText(text, byteLength, *font, encoding).release();
 }
 
+namespace {
+    sk_sp<SkData> serializeTypeface(SkTypeface* typeface, void* ctx) {
+        auto behavior = *static_cast<SkTypeface::SerializeBehavior*>(ctx);
+        return typeface->serialize(behavior);
+    }
+
+    sk_sp<SkTypeface> deserializeTypeface(const void* data, size_t length, void*) {
+        SkMemoryStream stream(data, length, false);
+        return SkTypeface::MakeDeserialize(&stream);
+    }
+}
+
+extern "C" SkData* C_SkTextBlob_serialize(const SkTextBlob* self, SkTypeface::SerializeBehavior typefaceBehavior) {
+    SkSerialProcs procs;
+    procs.fTypefaceProc = serializeTypeface;
+    procs.fTypefaceCtx = &typefaceBehavior;
+    return self->serialize(procs).release();
+}
+
+extern "C" SkTextBlob* C_SkTextBlob_Deserialize(const void* data, size_t length) {
+    SkDeserialProcs procs;
+    procs.fTypefaceProc = deserializeTypeface;
+    return SkTextBlob::Deserialize(data, length, procs).release();
+}
+
+extern "C" SkTextBlobRunIterator* C_SkTextBlobRunIterator_new(const SkTextBlob* blob) {
+    return new SkTextBlobRunIterator(blob);
+}
+
+extern "C" void C_SkTextBlobRunIterator_delete(SkTextBlobRunIterator* self) {
+    delete self;
+}
+
+extern "C" bool C_SkTextBlobRunIterator_done(const SkTextBlobRunIterator* self) {
+    return self->done();
+}
+
+extern "C" void C_SkTextBlobRunIterator_next(SkTextBlobRunIterator* self) {
+    self->next();
+}
+
+extern "C" uint32_t C_SkTextBlobRunIterator_glyphCount(const SkTextBlobRunIterator* self) {
+    return self->glyphCount();
+}
+
+extern "C" const uint16_t* C_SkTextBlobRunIterator_glyphs(const SkTextBlobRunIterator* self) {
+    return self->glyphs();
+}
+
+extern "C" const SkScalar* C_SkTextBlobRunIterator_pos(const SkTextBlobRunIterator* self) {
+    return self->pos();
+}
+
+extern "C" const SkRSXform* C_SkTextBlobRunIterator_xforms(const SkTextBlobRunIterator* self) {
+    return self->xforms();
+}
+
+extern "C" void C_SkTextBlobRunIterator_offset(const SkTextBlobRunIterator* self, SkPoint* offset) {
+    *offset = self->offset();
+}
+
+extern "C" const SkFont* C_SkTextBlobRunIterator_font(const SkTextBlobRunIterator* self) {
+    return &self->font();
+}
+
+extern "C" int C_SkTextBlobRunIterator_positioning(const SkTextBlobRunIterator* self) {
+    return static_cast<int>(self->positioning());
+}
+
+extern "C" const uint32_t* C_SkTextBlobRunIterator_clusters(const SkTextBlobRunIterator* self) {
+    return self->clusters();
+}
+
+extern "C" const char* C_SkTextBlobRunIterator_text(const SkTextBlobRunIterator* self, uint32_t* textSize) {
+    *textSize = self->textSize();
+    return self->text();
+}
+
 extern "C" void C_SkTextBlobBuilder_destruct(SkTextBlobBuilder* self) {
     self->~SkTextBlobBuilder();
 }
@@ -1021,6 +1494,14 @@ extern "C" SkTypeface* C_SkTypeface_makeClone(const SkTypeface* self, const SkFo
     return self->makeClone(*arguments).release();
 }
 
+extern "C" SkData* C_SkTypeface_openStreamData(const SkTypeface* self, int* ttcIndex) {
+    auto stream = self->openStream(ttcIndex);
+    if (!stream) {
+        return nullptr;
+    }
+    return SkData::MakeFromStream(stream.get(), stream->getLength()).release();
+}
+
 extern "C" SkData* C_SkTypeface_serialize(const SkTypeface* self, SkTypeface::SerializeBehavior behavior) {
     return self->serialize(behavior).release();
 }
@@ -1063,8 +1544,9 @@ extern "C" const char* C_SkFlattenable_getTypeName(const SkFlattenable* self) {
     return self->getTypeName();
 }
 
-extern "C" SkData* C_SkFlattenable_serialize(const SkFlattenable* self) {
-    return self->serialize().release();
+extern "C" SkData* C_SkFlattenable_serialize(const SkFlattenable* self, const RustSerialProcs* procs) {
+    auto serialProcs = makeSerialProcs(procs);
+    return self->serialize(&serialProcs).release();
 }
 
 //
@@ -1103,6 +1585,10 @@ extern "C" void C_SkFont_destruct(SkFont* self) {
     self->~SkFont();
 }
 
+extern "C" void C_SkFont_CopyConstruct(SkFont* uninitialized, const SkFont* font) {
+    new(uninitialized) SkFont(*font);
+}
+
 //
 // core/SkFontArguments.h
 //
@@ -1153,10 +1639,237 @@ extern "C" SkTypeface* C_SkFontMgr_makeFromStream(const SkFontMgr* self, SkStrea
     return self->makeFromStream(std::unique_ptr<SkStreamAsset>(stream), ttcIndex).release();
 }
 
+// Typefaces may duplicate their stream later on, which Rust streams do not support, so the
+// font data is read into memory first.
+// note: this function _consumes_ / deletes the stream.
+extern "C" SkTypeface* C_SkFontMgr_makeFromRustStream(const SkFontMgr* self, SkStream* stream, int ttcIndex) {
+    std::unique_ptr<SkStream> owned(stream);
+    auto data = SkData::MakeFromStream(owned.get(), owned->getLength());
+    return self->makeFromData(std::move(data), ttcIndex).release();
+}
+
+// note: this function _consumes_ / deletes the stream.
+extern "C" SkTypeface* C_SkFontMgr_makeFromRustStreamArgs(const SkFontMgr* self, SkStream* stream, const SkFontArguments* args) {
+    std::unique_ptr<SkStream> owned(stream);
+    auto data = SkData::MakeFromStream(owned.get(), owned->getLength());
+    return self->makeFromStream(SkMemoryStream::Make(std::move(data)), *args).release();
+}
+
 extern "C" SkFontMgr* C_SkFontMgr_RefDefault() {
     return SkFontMgr::RefDefault().release();
 }
 
+//
+// A font manager that resolves only against explicitly registered typefaces.
+//
+
+class TypefaceFontStyleSet : public SkFontStyleSet {
+public:
+    explicit TypefaceFontStyleSet(const SkString& familyName)
+        : fFamilyName(familyName) {}
+
+    int count() override {
+        return static_cast<int>(fStyles.size());
+    }
+
+    void getStyle(int index, SkFontStyle* style, SkString* name) override {
+        SkASSERT(index >= 0 && index < count());
+        if (style) {
+            *style = fStyles[index]->fontStyle();
+        }
+        if (name) {
+            name->reset();
+        }
+    }
+
+    SkTypeface* createTypeface(int index) override {
+        SkASSERT(index >= 0 && index < count());
+        return SkRef(fStyles[index].get());
+    }
+
+    SkTypeface* matchStyle(const SkFontStyle& pattern) override {
+        return matchStyleCSS3(pattern);
+    }
+
+    const SkString& familyName() const {
+        return fFamilyName;
+    }
+
+    void appendTypeface(sk_sp<SkTypeface> typeface) {
+        fStyles.push_back(std::move(typeface));
+    }
+
+private:
+    SkString fFamilyName;
+    std::vector<sk_sp<SkTypeface>> fStyles;
+};
+
+class TypefaceFontProvider : public SkFontMgr {
+public:
+    size_t registerTypeface(sk_sp<SkTypeface> typeface, const SkString& familyName) {
+        if (familyName.isEmpty()) {
+            return 0;
+        }
+
+        TypefaceFontStyleSet* styleSet = findFamily(familyName.c_str());
+        if (!styleSet) {
+            fFamilies.push_back(sk_make_sp<TypefaceFontStyleSet>(familyName));
+            styleSet = fFamilies.back().get();
+        }
+        styleSet->appendTypeface(std::move(typeface));
+        return 1;
+    }
+
+protected:
+    int onCountFamilies() const override {
+        return static_cast<int>(fFamilies.size());
+    }
+
+    void onGetFamilyName(int index, SkString* familyName) const override {
+        SkASSERT(index >= 0 && index < onCountFamilies());
+        *familyName = fFamilies[index]->familyName();
+    }
+
+    SkFontStyleSet* onCreateStyleSet(int index) const override {
+        SkASSERT(index >= 0 && index < onCountFamilies());
+        return SkRef(fFamilies[index].get());
+    }
+
+    SkFontStyleSet* onMatchFamily(const char familyName[]) const override {
+        TypefaceFontStyleSet* styleSet = findFamily(familyName);
+        return styleSet ? SkRef(styleSet) : SkFontStyleSet::CreateEmpty();
+    }
+
+    SkTypeface* onMatchFamilyStyle(const char familyName[], const SkFontStyle& style) const override {
+        TypefaceFontStyleSet* styleSet = findFamily(familyName);
+        return styleSet ? styleSet->matchStyle(style) : nullptr;
+    }
+
+    SkTypeface* onMatchFamilyStyleCharacter(const char familyName[], const SkFontStyle& style,
+                                            const char* bcp47[], int bcp47Count,
+                                            SkUnichar character) const override {
+        // "und-Zsye" requests the emoji presentation, so color typefaces are tried first.
+        bool preferColor = false;
+        for (int i = 0; i < bcp47Count; ++i) {
+            if (bcp47[i] && strcmp(bcp47[i], "und-Zsye") == 0) {
+                preferColor = true;
+            }
+        }
+
+        TypefaceFontStyleSet* preferred = findFamily(familyName);
+        if (preferColor) {
+            if (SkTypeface* typeface = matchCharacter(preferred, style, character, true)) {
+                return typeface;
+            }
+        }
+        return matchCharacter(preferred, style, character, false);
+    }
+
+    SkTypeface* onMatchFaceStyle(const SkTypeface* typeface, const SkFontStyle& style) const override {
+        SkString familyName;
+        typeface->getFamilyName(&familyName);
+        return onMatchFamilyStyle(familyName.c_str(), style);
+    }
+
+    // Typefaces are created from the caller's data, so creating them does not depend on the fonts
+    // that are installed on the system.
+
+    sk_sp<SkTypeface> onMakeFromData(sk_sp<SkData> data, int ttcIndex) const override {
+        return SkFontMgr::RefDefault()->makeFromData(std::move(data), ttcIndex);
+    }
+
+    sk_sp<SkTypeface> onMakeFromStreamIndex(std::unique_ptr<SkStreamAsset> stream, int ttcIndex) const override {
+        return SkFontMgr::RefDefault()->makeFromStream(std::move(stream), ttcIndex);
+    }
+
+    sk_sp<SkTypeface> onMakeFromStreamArgs(std::unique_ptr<SkStreamAsset> stream, const SkFontArguments& args) const override {
+        return SkFontMgr::RefDefault()->makeFromStream(std::move(stream), args);
+    }
+
+    sk_sp<SkTypeface> onMakeFromFile(const char path[], int ttcIndex) const override {
+        return SkFontMgr::RefDefault()->makeFromFile(path, ttcIndex);
+    }
+
+    sk_sp<SkTypeface> onLegacyMakeTypeface(const char familyName[], SkFontStyle style) const override {
+        if (familyName) {
+            if (SkTypeface* typeface = onMatchFamilyStyle(familyName, style)) {
+                return sk_sp<SkTypeface>(typeface);
+            }
+        }
+        // fall back to the first registered family.
+        if (!fFamilies.empty()) {
+            return sk_sp<SkTypeface>(fFamilies.front()->matchStyle(style));
+        }
+        return nullptr;
+    }
+
+private:
+    static bool hasColorGlyphs(const SkTypeface* typeface) {
+        return typeface->getTableSize(SkSetFourByteTag('C', 'O', 'L', 'R'))
+            || typeface->getTableSize(SkSetFourByteTag('C', 'B', 'D', 'T'))
+            || typeface->getTableSize(SkSetFourByteTag('s', 'b', 'i', 'x'));
+    }
+
+    static SkTypeface* matchCharacterInFamily(TypefaceFontStyleSet* styleSet, const SkFontStyle& style,
+                                              SkUnichar character, bool colorOnly) {
+        sk_sp<SkTypeface> typeface(styleSet->matchStyle(style));
+        if (!typeface || !typeface->unicharToGlyph(character)) {
+            return nullptr;
+        }
+        if (colorOnly && !hasColorGlyphs(typeface.get())) {
+            return nullptr;
+        }
+        return typeface.release();
+    }
+
+    // Tries the preferred family first and then all the other families in registration order.
+    SkTypeface* matchCharacter(TypefaceFontStyleSet* preferred, const SkFontStyle& style,
+                               SkUnichar character, bool colorOnly) const {
+        if (preferred) {
+            if (SkTypeface* typeface = matchCharacterInFamily(preferred, style, character, colorOnly)) {
+                return typeface;
+            }
+        }
+        for (const auto& styleSet : fFamilies) {
+            if (styleSet.get() == preferred) {
+                continue;
+            }
+            if (SkTypeface* typeface = matchCharacterInFamily(styleSet.get(), style, character, colorOnly)) {
+                return typeface;
+            }
+        }
+        return nullptr;
+    }
+
+    TypefaceFontStyleSet* findFamily(const char familyName[]) const {
+        if (!familyName) {
+            return nullptr;
+        }
+        for (const auto& styleSet : fFamilies) {
+            if (styleSet->familyName().equals(familyName)) {
+                return styleSet.get();
+            }
+        }
+        return nullptr;
+    }
+
+    std::vector<sk_sp<TypefaceFontStyleSet>> fFamilies;
+};
+
+extern "C" SkFontMgr* C_TypefaceFontProvider_new() {
+    return new TypefaceFontProvider();
+}
+
+extern "C" size_t C_TypefaceFontProvider_registerTypeface(SkFontMgr* self, SkTypeface* typeface, const char* alias) {
+    SkString familyName;
+    if (alias) {
+        familyName = alias;
+    } else {
+        typeface->getFamilyName(&familyName);
+    }
+    return static_cast<TypefaceFontProvider*>(self)->registerTypeface(sk_sp<SkTypeface>(typeface), familyName);
+}
+
 //
 // core/SkFontParameters.h
 //
@@ -1226,6 +1939,18 @@ extern "C" SkVertices* C_SkVertices_Builder_detach(SkVertices::Builder* builder)
     return builder->detach().release();
 }
 
+//
+// SkBBHFactory
+//
+
+extern "C" void C_SkRTreeFactory_construct(SkBBHFactory* uninitialized) {
+    new(uninitialized) SkRTreeFactory();
+}
+
+extern "C" void C_SkBBHFactory_destruct(SkBBHFactory* self) {
+    self->~SkBBHFactory();
+}
+
 //
 // SkPictureRecorder
 //
@@ -1266,8 +1991,9 @@ extern "C" uint32_t C_SkColorFilter_getFlags(const SkColorFilter* self) {
     return self->getFlags();
 }
 
-extern "C" SkColorFilter* C_SkColorFilter_Deserialize(const void* data, size_t size) {
-    return SkColorFilter::Deserialize(data, size).release();
+extern "C" SkColorFilter* C_SkColorFilter_Deserialize(const void* data, size_t size, const RustDeserialProcs* procs) {
+    auto deserialProcs = makeDeserialProcs(procs);
+    return SkColorFilter::Deserialize(data, size, &deserialProcs).release();
 }
 
 //
@@ -1351,8 +2077,9 @@ extern "C" bool C_SkDrawLooper_asABlurShadow(const SkDrawLooper* self, SkDrawLoo
     return self->asABlurShadow(&br);
 }
 
-extern "C" SkDrawLooper* C_SkDrawLooper_Deserialize(const void* data, size_t length) {
-    return SkDrawLooper::Deserialize(data, length).release();
+extern "C" SkDrawLooper* C_SkDrawLooper_Deserialize(const void* data, size_t length, const RustDeserialProcs* procs) {
+    auto deserialProcs = makeDeserialProcs(procs);
+    return SkDrawLooper::Deserialize(data, length, &deserialProcs).release();
 }
 
 //
@@ -1366,8 +2093,9 @@ extern "C" SkDrawable::GpuDrawHandler *C_SkDrawable_snapGpuDrawHandler(SkDrawabl
     return self->snapGpuDrawHandler(backendApi, *matrix, *clipBounds, *bufferInfo).release();
 }
 
-extern "C" SkDrawable* C_SkDrawable_Deserialize(const void* data, size_t length) {
-    return SkDrawable::Deserialize(data, length).release();
+extern "C" SkDrawable* C_SkDrawable_Deserialize(const void* data, size_t length, const RustDeserialProcs* procs) {
+    auto deserialProcs = makeDeserialProcs(procs);
+    return SkDrawable::Deserialize(data, length, &deserialProcs).release();
 }
 
 extern "C" void C_SkDrawable_GpuDrawHandler_destruct(SkDrawable::GpuDrawHandler *self) {
@@ -1394,8 +2122,9 @@ extern "C" SkImageFilter* C_SkImageFilter_MakeMatrixFilter(const SkMatrix* matri
     return SkImageFilter::MakeMatrixFilter(*matrix, quality, spFromConst(input)).release();
 }
 
-extern "C" SkImageFilter* C_SkImageFilter_Deserialize(const void* data, size_t length) {
-    return SkImageFilter::Deserialize(data, length).release();
+extern "C" SkImageFilter* C_SkImageFilter_Deserialize(const void* data, size_t length, const RustDeserialProcs* procs) {
+    auto deserialProcs = makeDeserialProcs(procs);
+    return SkImageFilter::Deserialize(data, length, &deserialProcs).release();
 }
 
 //
@@ -1475,8 +2204,9 @@ extern "C" void C_SkPathEffect_PointData_deletePoints(SkPathEffect::PointData* s
     self->fPoints = nullptr;
 }
 
-extern "C" SkPathEffect* C_SkPathEffect_Deserialize(const void* data, size_t length) {
-    return SkPathEffect::Deserialize(data, length).release();
+extern "C" SkPathEffect* C_SkPathEffect_Deserialize(const void* data, size_t length, const RustDeserialProcs* procs) {
+    auto deserialProcs = makeDeserialProcs(procs);
+    return SkPathEffect::Deserialize(data, length, &deserialProcs).release();
 }
 
 //
@@ -1511,8 +2241,9 @@ extern "C" SkMaskFilter* C_SkMaskFilter_makeWithMatrix(const SkMaskFilter* self,
     return self->makeWithMatrix(*matrix).release();
 }
 
-extern "C" SkMaskFilter* C_SkMaskFilter_Deserialize(const void* data, size_t length) {
-    return SkMaskFilter::Deserialize(data, length).release();
+extern "C" SkMaskFilter* C_SkMaskFilter_Deserialize(const void* data, size_t length, const RustDeserialProcs* procs) {
+    auto deserialProcs = makeDeserialProcs(procs);
+    return SkMaskFilter::Deserialize(data, length, &deserialProcs).release();
 }
 
 //
@@ -1571,8 +2302,9 @@ extern "C" SkShader* C_SkShaders_Lerp2(const SkShader* red, const SkShader* dst,
     return SkShaders::Lerp(spFromConst(red), spFromConst(dst), spFromConst(src)).release();
 }
 
-extern "C" SkShader* C_SkShader_Deserialize(const void* data, size_t length) {
-    return dynamic_cast<SkShader*>(SkShader::Deserialize(SkFlattenable::Type::kSkShaderBase_Type, data, length).release());
+extern "C" SkShader* C_SkShader_Deserialize(const void* data, size_t length, const RustDeserialProcs* procs) {
+    auto deserialProcs = makeDeserialProcs(procs);
+    return dynamic_cast<SkShader*>(SkShader::Deserialize(SkFlattenable::Type::kSkShaderBase_Type, data, length, &deserialProcs).release());
 }
 
 //
@@ -1591,6 +2323,10 @@ extern "C" void C_SkWStream_destruct(SkWStream* self) {
     self->~SkWStream();
 }
 
+extern "C" void C_SkWStream_delete(SkWStream* self) {
+    delete self;
+}
+
 extern "C" bool C_SkWStream_write(SkWStream* self, const void* buffer, size_t size) {
     return self->write(buffer, size);
 }
@@ -1619,6 +2355,148 @@ extern "C" SkStreamAsset* C_SkDynamicMemoryWStream_detachAsStream(SkDynamicMemor
     return self->detachAsStream().release();
 }
 
+//
+// RustStream: public SkStreamAsset
+// A stream that forwards all reads and seeks to a Rust stream.
+//
+
+typedef size_t (*RustStream_Read)(void* data, void* buffer, size_t count);
+typedef bool (*RustStream_Seek)(void* data, size_t position);
+typedef void (*RustStream_Drop)(void* data);
+
+class RustStream : public SkStreamAsset {
+public:
+    RustStream(void* data, size_t length, RustStream_Read read, RustStream_Seek seek, RustStream_Drop drop)
+        : fData(data), fLength(length), fPosition(0), fRead(read), fSeek(seek), fDrop(drop) {}
+
+    ~RustStream() override {
+        fDrop(fData);
+    }
+
+    size_t read(void* buffer, size_t size) override {
+        size = std::min(size, fLength - fPosition);
+        if (!buffer) {
+            // skip
+            return this->seek(fPosition + size) ? size : 0;
+        }
+        size_t bytesRead = fRead(fData, buffer, size);
+        fPosition += bytesRead;
+        return bytesRead;
+    }
+
+    size_t peek(void* buffer, size_t size) const override {
+        auto self = const_cast<RustStream*>(this);
+        size_t position = fPosition;
+        size_t bytesRead = self->read(buffer, size);
+        if (!self->seek(position)) {
+            return 0;
+        }
+        return bytesRead;
+    }
+
+    bool isAtEnd() const override {
+        return fPosition >= fLength;
+    }
+
+    bool rewind() override {
+        return this->seek(0);
+    }
+
+    bool hasPosition() const override {
+        return true;
+    }
+
+    size_t getPosition() const override {
+        return fPosition;
+    }
+
+    bool seek(size_t position) override {
+        position = std::min(position, fLength);
+        if (!fSeek(fData, position)) {
+            return false;
+        }
+        fPosition = position;
+        return true;
+    }
+
+    bool move(long offset) override {
+        if (offset < 0 && static_cast<size_t>(-offset) > fPosition) {
+            return this->seek(0);
+        }
+        return this->seek(fPosition + offset);
+    }
+
+    bool hasLength() const override {
+        return true;
+    }
+
+    size_t getLength() const override {
+        return fLength;
+    }
+
+private:
+    // Rust streams can not be duplicated or forked, so both are unsupported and return null.
+
+    SkStreamAsset* onDuplicate() const override {
+        return nullptr;
+    }
+
+    SkStreamAsset* onFork() const override {
+        return nullptr;
+    }
+
+    void* fData;
+    size_t fLength;
+    size_t fPosition;
+    RustStream_Read fRead;
+    RustStream_Seek fSeek;
+    RustStream_Drop fDrop;
+};
+
+extern "C" SkStreamAsset* C_RustStream_new(void* data, size_t length, RustStream_Read read, RustStream_Seek seek, RustStream_Drop drop) {
+    return new RustStream(data, length, read, seek, drop);
+}
+
+//
+// RustWStream: public SkWStream
+// A stream that forwards all writes to a Rust writer.
+//
+
+typedef bool (*RustWStream_Write)(void* data, const void* buffer, size_t count);
+typedef void (*RustWStream_Flush)(void* data);
+
+class RustWStream : public SkWStream {
+public:
+    RustWStream(void* data, RustWStream_Write write, RustWStream_Flush flush)
+        : fData(data), fBytesWritten(0), fWrite(write), fFlush(flush) {}
+
+    bool write(const void* buffer, size_t size) override {
+        if (!fWrite(fData, buffer, size)) {
+            return false;
+        }
+        fBytesWritten += size;
+        return true;
+    }
+
+    void flush() override {
+        fFlush(fData);
+    }
+
+    size_t bytesWritten() const override {
+        return fBytesWritten;
+    }
+
+private:
+    void* fData;
+    size_t fBytesWritten;
+    RustWStream_Write fWrite;
+    RustWStream_Flush fFlush;
+};
+
+extern "C" SkWStream* C_RustWStream_new(void* data, RustWStream_Write write, RustWStream_Flush flush) {
+    return new RustWStream(data, write, flush);
+}
+
 //
 // SkGradientShader
 //
@@ -2023,6 +2901,10 @@ extern "C" SkDocument* C_SkPDF_MakeDocument(SkWStream* stream, const SkPDF::Meta
     return SkPDF::MakeDocument(stream, *metadata).release();
 }
 
+extern "C" void C_SkPDF_SetNodeId(SkCanvas* dst, int nodeID) {
+    SkPDF::SetNodeId(dst, nodeID);
+}
+
 //
 // GrBackendFormat
 //
@@ -2063,6 +2945,35 @@ extern "C" bool C_GrGLFramebufferInfo_Equals(const GrGLFramebufferInfo* lhs, con
     return *lhs == *rhs;
 }
 
+//
+// encode/SkPngEncoder.h
+//
+
+// note: filterFlags is a combination of SkPngEncoder::FilterFlag values.
+extern "C" bool C_SkPngEncoder_Encode(SkWStream* dst, const SkPixmap* src, int filterFlags, int zlibLevel, SkDataTable* comments) {
+    SkPngEncoder::Options options;
+    options.fFilterFlags = static_cast<SkPngEncoder::FilterFlag>(filterFlags);
+    options.fZLibLevel = zlibLevel;
+    options.fComments = sk_ref_sp(comments);
+    return SkPngEncoder::Encode(dst, *src, options);
+}
+
+//
+// encode/SkJpegEncoder.h
+//
+
+extern "C" bool C_SkJpegEncoder_Encode(SkWStream* dst, const SkPixmap* src, const SkJpegEncoder::Options* options) {
+    return SkJpegEncoder::Encode(dst, *src, *options);
+}
+
+//
+// encode/SkWebpEncoder.h
+//
+
+extern "C" bool C_SkWebpEncoder_Encode(SkWStream* dst, const SkPixmap* src, const SkWebpEncoder::Options* options) {
+    return SkWebpEncoder::Encode(dst, *src, *options);
+}
+
 //
 // GrGLInterface
 //
@@ -2158,6 +3069,210 @@ extern "C" SkCanvas* C_SkMakeNullCanvas() {
     return SkMakeNullCanvas().release();
 }
 
+//
+// RustCanvas: public SkNoDrawCanvas
+// A canvas that forwards matrix, clip and draw calls to a Rust handler.
+// The matrix and clip state is maintained by the canvas itself.
+//
+
+// The draw calls that are forwarded without their arguments.
+enum class RustCanvas_OtherDraw {
+    Annotation,
+    Patch,
+    ImageNine,
+    ImageLattice,
+    Bitmap,
+    BitmapRect,
+    BitmapNine,
+    BitmapLattice,
+    Vertices,
+    Atlas,
+    ShadowRec,
+    EdgeAAQuad,
+    EdgeAAImageSet,
+};
+
+struct RustCanvas_Handler {
+    void* data;
+    void (*save)(void* data);
+    void (*saveLayer)(void* data, const SkRect* bounds, const SkPaint* paint);
+    void (*restore)(void* data);
+    void (*concat)(void* data, const SkMatrix* matrix);
+    void (*setMatrix)(void* data, const SkMatrix* matrix);
+    void (*clipRect)(void* data, const SkRect* rect, SkClipOp op, bool doAntiAlias);
+    void (*clipRRect)(void* data, const SkRRect* rrect, SkClipOp op, bool doAntiAlias);
+    void (*clipPath)(void* data, const SkPath* path, SkClipOp op, bool doAntiAlias);
+    void (*clipRegion)(void* data, const SkRegion* region, SkClipOp op);
+    void (*drawPaint)(void* data, const SkPaint* paint);
+    void (*drawPoints)(void* data, SkCanvas::PointMode mode, size_t count, const SkPoint pts[], const SkPaint* paint);
+    void (*drawRect)(void* data, const SkRect* rect, const SkPaint* paint);
+    void (*drawRRect)(void* data, const SkRRect* rrect, const SkPaint* paint);
+    void (*drawDRRect)(void* data, const SkRRect* outer, const SkRRect* inner, const SkPaint* paint);
+    void (*drawOval)(void* data, const SkRect* oval, const SkPaint* paint);
+    void (*drawArc)(void* data, const SkRect* oval, SkScalar startAngle, SkScalar sweepAngle, bool useCenter, const SkPaint* paint);
+    void (*drawPath)(void* data, const SkPath* path, const SkPaint* paint);
+    void (*drawRegion)(void* data, const SkRegion* region, const SkPaint* paint);
+    void (*drawTextBlob)(void* data, const SkTextBlob* blob, SkScalar x, SkScalar y, const SkPaint* paint);
+    void (*drawImage)(void* data, const SkImage* image, SkScalar left, SkScalar top, const SkPaint* paint);
+    void (*drawImageRect)(void* data, const SkImage* image, const SkRect* src, const SkRect* dst, const SkPaint* paint, SkCanvas::SrcRectConstraint constraint);
+    void (*drawOther)(void* data, RustCanvas_OtherDraw draw);
+};
+
+class RustCanvas : public SkNoDrawCanvas {
+public:
+    RustCanvas(const SkIRect& bounds, const RustCanvas_Handler& handler)
+        : SkNoDrawCanvas(bounds), fHandler(handler) {}
+
+protected:
+    void willSave() override {
+        fHandler.save(fHandler.data);
+    }
+
+    SaveLayerStrategy getSaveLayerStrategy(const SaveLayerRec& rec) override {
+        fHandler.saveLayer(fHandler.data, rec.fBounds, rec.fPaint);
+        return SkNoDrawCanvas::getSaveLayerStrategy(rec);
+    }
+
+    void willRestore() override {
+        fHandler.restore(fHandler.data);
+    }
+
+    void didConcat(const SkMatrix& matrix) override {
+        fHandler.concat(fHandler.data, &matrix);
+    }
+
+    void didSetMatrix(const SkMatrix& matrix) override {
+        fHandler.setMatrix(fHandler.data, &matrix);
+    }
+
+    void onClipRect(const SkRect& rect, SkClipOp op, ClipEdgeStyle edgeStyle) override {
+        fHandler.clipRect(fHandler.data, &rect, op, edgeStyle == kSoft_ClipEdgeStyle);
+        SkNoDrawCanvas::onClipRect(rect, op, edgeStyle);
+    }
+
+    void onClipRRect(const SkRRect& rrect, SkClipOp op, ClipEdgeStyle edgeStyle) override {
+        fHandler.clipRRect(fHandler.data, &rrect, op, edgeStyle == kSoft_ClipEdgeStyle);
+        SkNoDrawCanvas::onClipRRect(rrect, op, edgeStyle);
+    }
+
+    void onClipPath(const SkPath& path, SkClipOp op, ClipEdgeStyle edgeStyle) override {
+        fHandler.clipPath(fHandler.data, &path, op, edgeStyle == kSoft_ClipEdgeStyle);
+        SkNoDrawCanvas::onClipPath(path, op, edgeStyle);
+    }
+
+    void onClipRegion(const SkRegion& region, SkClipOp op) override {
+        fHandler.clipRegion(fHandler.data, &region, op);
+        SkNoDrawCanvas::onClipRegion(region, op);
+    }
+
+    void onDrawPaint(const SkPaint& paint) override {
+        fHandler.drawPaint(fHandler.data, &paint);
+    }
+
+    void onDrawPoints(PointMode mode, size_t count, const SkPoint pts[], const SkPaint& paint) override {
+        fHandler.drawPoints(fHandler.data, mode, count, pts, &paint);
+    }
+
+    void onDrawRect(const SkRect& rect, const SkPaint& paint) override {
+        fHandler.drawRect(fHandler.data, &rect, &paint);
+    }
+
+    void onDrawRRect(const SkRRect& rrect, const SkPaint& paint) override {
+        fHandler.drawRRect(fHandler.data, &rrect, &paint);
+    }
+
+    void onDrawDRRect(const SkRRect& outer, const SkRRect& inner, const SkPaint& paint) override {
+        fHandler.drawDRRect(fHandler.data, &outer, &inner, &paint);
+    }
+
+    void onDrawOval(const SkRect& oval, const SkPaint& paint) override {
+        fHandler.drawOval(fHandler.data, &oval, &paint);
+    }
+
+    void onDrawArc(const SkRect& oval, SkScalar startAngle, SkScalar sweepAngle, bool useCenter, const SkPaint& paint) override {
+        fHandler.drawArc(fHandler.data, &oval, startAngle, sweepAngle, useCenter, &paint);
+    }
+
+    void onDrawPath(const SkPath& path, const SkPaint& paint) override {
+        fHandler.drawPath(fHandler.data, &path, &paint);
+    }
+
+    void onDrawRegion(const SkRegion& region, const SkPaint& paint) override {
+        fHandler.drawRegion(fHandler.data, &region, &paint);
+    }
+
+    void onDrawTextBlob(const SkTextBlob* blob, SkScalar x, SkScalar y, const SkPaint& paint) override {
+        fHandler.drawTextBlob(fHandler.data, blob, x, y, &paint);
+    }
+
+    void onDrawImage(const SkImage* image, SkScalar left, SkScalar top, const SkPaint* paint) override {
+        fHandler.drawImage(fHandler.data, image, left, top, paint);
+    }
+
+    void onDrawImageRect(const SkImage* image, const SkRect* src, const SkRect& dst, const SkPaint* paint, SrcRectConstraint constraint) override {
+        fHandler.drawImageRect(fHandler.data, image, src, &dst, paint, constraint);
+    }
+
+    void onDrawAnnotation(const SkRect&, const char[], SkData*) override {
+        fHandler.drawOther(fHandler.data, RustCanvas_OtherDraw::Annotation);
+    }
+
+    void onDrawPatch(const SkPoint[12], const SkColor[4], const SkPoint[4], SkBlendMode, const SkPaint&) override {
+        fHandler.drawOther(fHandler.data, RustCanvas_OtherDraw::Patch);
+    }
+
+    void onDrawImageNine(const SkImage*, const SkIRect&, const SkRect&, const SkPaint*) override {
+        fHandler.drawOther(fHandler.data, RustCanvas_OtherDraw::ImageNine);
+    }
+
+    void onDrawImageLattice(const SkImage*, const Lattice&, const SkRect&, const SkPaint*) override {
+        fHandler.drawOther(fHandler.data, RustCanvas_OtherDraw::ImageLattice);
+    }
+
+    void onDrawBitmap(const SkBitmap&, SkScalar, SkScalar, const SkPaint*) override {
+        fHandler.drawOther(fHandler.data, RustCanvas_OtherDraw::Bitmap);
+    }
+
+    void onDrawBitmapRect(const SkBitmap&, const SkRect*, const SkRect&, const SkPaint*, SrcRectConstraint) override {
+        fHandler.drawOther(fHandler.data, RustCanvas_OtherDraw::BitmapRect);
+    }
+
+    void onDrawBitmapNine(const SkBitmap&, const SkIRect&, const SkRect&, const SkPaint*) override {
+        fHandler.drawOther(fHandler.data, RustCanvas_OtherDraw::BitmapNine);
+    }
+
+    void onDrawBitmapLattice(const SkBitmap&, const Lattice&, const SkRect&, const SkPaint*) override {
+        fHandler.drawOther(fHandler.data, RustCanvas_OtherDraw::BitmapLattice);
+    }
+
+    void onDrawVerticesObject(const SkVertices*, const SkVertices::Bone[], int, SkBlendMode, const SkPaint&) override {
+        fHandler.drawOther(fHandler.data, RustCanvas_OtherDraw::Vertices);
+    }
+
+    void onDrawAtlas(const SkImage*, const SkRSXform[], const SkRect[], const SkColor[], int, SkBlendMode, const SkRect*, const SkPaint*) override {
+        fHandler.drawOther(fHandler.data, RustCanvas_OtherDraw::Atlas);
+    }
+
+    void onDrawShadowRec(const SkPath&, const SkDrawShadowRec&) override {
+        fHandler.drawOther(fHandler.data, RustCanvas_OtherDraw::ShadowRec);
+    }
+
+    void onDrawEdgeAAQuad(const SkRect&, const SkPoint[4], QuadAAFlags, SkColor, SkBlendMode) override {
+        fHandler.drawOther(fHandler.data, RustCanvas_OtherDraw::EdgeAAQuad);
+    }
+
+    void onDrawEdgeAAImageSet(const ImageSetEntry[], int, const SkPoint[], const SkMatrix[], const SkPaint*, SrcRectConstraint) override {
+        fHandler.drawOther(fHandler.data, RustCanvas_OtherDraw::EdgeAAImageSet);
+    }
+
+private:
+    RustCanvas_Handler fHandler;
+};
+
+extern "C" SkCanvas* C_RustCanvas_new(const SkIRect* bounds, const RustCanvas_Handler* handler) {
+    return new RustCanvas(*bounds, *handler);
+}
+
 #if defined(SK_VULKAN)
 
 // The GrVkBackendContext struct binding's length is too short
@@ -2249,3 +3364,291 @@ extern "C" SkCanvas* C_SkSVGCanvas_Make(const SkRect* bounds, SkWStream* writer)
 }
 
 #endif
+
+#if defined(SK_ENABLE_SKOTTIE)
+
+//
+// modules/skottie/include/Skottie.h
+//
+
+extern "C" void C_skottie_Animation_ref(const skottie::Animation* self) {
+    self->ref();
+}
+
+extern "C" void C_skottie_Animation_unref(const skottie::Animation* self) {
+    self->unref();
+}
+
+extern "C" bool C_skottie_Animation_unique(const skottie::Animation* self) {
+    return self->unique();
+}
+
+// note: this function _consumes_ the references to the font manager and the resource provider.
+extern "C" skottie::Animation* C_skottie_Animation_Make(
+        const char* data, size_t length,
+        SkFontMgr* fontMgr,
+        skottie::ResourceProvider* resourceProvider) {
+    skottie::Animation::Builder builder;
+    builder.setFontManager(sk_sp<SkFontMgr>(fontMgr));
+    builder.setResourceProvider(sk_sp<skottie::ResourceProvider>(resourceProvider));
+    return builder.make(data, length).release();
+}
+
+extern "C" void C_skottie_Animation_version(const skottie::Animation* self, SkString* version) {
+    *version = self->version();
+}
+
+extern "C" SkScalar C_skottie_Animation_duration(const skottie::Animation* self) {
+    return self->duration();
+}
+
+extern "C" void C_skottie_Animation_size(const skottie::Animation* self, SkSize* size) {
+    *size = self->size();
+}
+
+extern "C" void C_skottie_Animation_seek(skottie::Animation* self, SkScalar t) {
+    self->seek(t);
+}
+
+extern "C" void C_skottie_Animation_render(const skottie::Animation* self, SkCanvas* canvas, const SkRect* dst) {
+    self->render(canvas, dst);
+}
+
+//
+// RustResourceProvider: public skottie::ResourceProvider
+// A resource provider that forwards all requests to a Rust resource provider.
+//
+
+typedef SkData* (*RustResourceProvider_Load)(void* data, const char* path, const char* name);
+typedef SkImage* (*RustResourceProvider_LoadImage)(void* data, const char* path, const char* name);
+typedef void (*RustResourceProvider_Drop)(void* data);
+
+class RustImageAsset : public skottie::ImageAsset {
+public:
+    RustImageAsset(sk_sp<SkImage> image) : fImage(std::move(image)) {}
+
+    bool isMultiFrame() override {
+        return false;
+    }
+
+    sk_sp<SkImage> getFrame(float) override {
+        return fImage;
+    }
+
+private:
+    sk_sp<SkImage> fImage;
+};
+
+class RustResourceProvider : public skottie::ResourceProvider {
+public:
+    RustResourceProvider(
+            void* data,
+            RustResourceProvider_Load load,
+            RustResourceProvider_LoadImage loadImage,
+            RustResourceProvider_Load loadFont,
+            RustResourceProvider_Drop drop)
+        : fData(data), fLoad(load), fLoadImage(loadImage), fLoadFont(loadFont), fDrop(drop) {}
+
+    ~RustResourceProvider() override {
+        fDrop(fData);
+    }
+
+    sk_sp<SkData> load(const char resource_path[], const char resource_name[]) const override {
+        return sk_sp<SkData>(fLoad(fData, resource_path, resource_name));
+    }
+
+    sk_sp<skottie::ImageAsset> loadImageAsset(const char resource_path[], const char resource_name[]) const override {
+        auto image = sk_sp<SkImage>(fLoadImage(fData, resource_path, resource_name));
+        if (!image) {
+            return nullptr;
+        }
+        return sk_make_sp<RustImageAsset>(std::move(image));
+    }
+
+    sk_sp<SkData> loadFont(const char name[], const char url[]) const override {
+        return sk_sp<SkData>(fLoadFont(fData, name, url));
+    }
+
+private:
+    void* fData;
+    RustResourceProvider_Load fLoad;
+    RustResourceProvider_LoadImage fLoadImage;
+    RustResourceProvider_Load fLoadFont;
+    RustResourceProvider_Drop fDrop;
+};
+
+extern "C" skottie::ResourceProvider* C_RustResourceProvider_new(
+        void* data,
+        RustResourceProvider_Load load,
+        RustResourceProvider_LoadImage loadImage,
+        RustResourceProvider_Load loadFont,
+        RustResourceProvider_Drop drop) {
+    return new RustResourceProvider(data, load, loadImage, loadFont, drop);
+}
+
+#endif
+
+#if defined(SK_ENABLE_PARTICLES)
+
+//
+// modules/particles/include/SkParticleEffect.h
+//
+
+extern "C" SkParticleEffectParams* C_SkParticleEffectParams_MakeFromJSON(const char* json, size_t length) {
+    static SkOnce once;
+    once([] { SkParticleEffect::RegisterParticleTypes(); });
+
+    skjson::DOM dom(json, length);
+    if (!dom.root().is<skjson::ObjectValue>()) {
+        return nullptr;
+    }
+    auto params = sk_make_sp<SkParticleEffectParams>();
+    SkFromJsonVisitor fromJson(dom.root());
+    params->visitFields(&fromJson);
+    return params.release();
+}
+
+extern "C" float C_SkParticleEffectParams_getRate(const SkParticleEffectParams* self) {
+    return self->fRate;
+}
+
+extern "C" void C_SkParticleEffectParams_setRate(SkParticleEffectParams* self, float rate) {
+    self->fRate = rate;
+}
+
+extern "C" SkParticleEffect* C_SkParticleEffect_Make(SkParticleEffectParams* params, uint32_t seed) {
+    return new SkParticleEffect(sk_ref_sp(params), SkRandom(seed));
+}
+
+extern "C" void C_SkParticleEffect_start(SkParticleEffect* self, double now, bool looping) {
+    self->start(now, looping);
+}
+
+extern "C" void C_SkParticleEffect_update(SkParticleEffect* self, double now) {
+    self->update(now);
+}
+
+extern "C" void C_SkParticleEffect_draw(SkParticleEffect* self, SkCanvas* canvas) {
+    self->draw(canvas);
+}
+
+extern "C" bool C_SkParticleEffect_isAlive(const SkParticleEffect* self) {
+    return self->isAlive();
+}
+
+extern "C" int C_SkParticleEffect_getCount(const SkParticleEffect* self) {
+    return self->getCount();
+}
+
+#endif
+
+#if defined(SK_ENABLE_SHAPER)
+
+//
+// modules/skshaper/include/SkShaper.h
+//
+
+extern "C" SkShaper* C_SkShaper_Make() {
+    return SkShaper::Make().release();
+}
+
+extern "C" SkShaper* C_SkShaper_MakePrimitive() {
+    return SkShaper::MakePrimitive().release();
+}
+
+extern "C" void C_SkShaper_delete(SkShaper* self) {
+    delete self;
+}
+
+extern "C" SkTextBlob* C_SkShaper_shape(
+        const SkShaper* self,
+        const char* utf8, size_t utf8Bytes,
+        const SkFont* font,
+        bool leftToRight,
+        SkScalar width,
+        const SkPoint* offset,
+        SkPoint* endPoint) {
+    SkTextBlobBuilderRunHandler handler(utf8, *offset);
+    self->shape(utf8, utf8Bytes, *font, leftToRight, width, &handler);
+    *endPoint = handler.endPoint();
+    return handler.makeBlob().release();
+}
+
+//
+// RustRunHandler: public SkShaper::RunHandler
+// A run handler that passes all shaped runs to Rust.
+//
+
+typedef void (*RustRunHandler_CommitRun)(
+        void* data,
+        const SkFont* font,
+        uint8_t bidiLevel,
+        const SkVector* advance,
+        size_t utf8Begin, size_t utf8End,
+        size_t line,
+        size_t glyphCount,
+        const SkGlyphID* glyphs,
+        const SkPoint* positions,
+        const uint32_t* clusters);
+
+class RustRunHandler : public SkShaper::RunHandler {
+public:
+    RustRunHandler(void* data, RustRunHandler_CommitRun commitRun)
+        : fData(data), fCommitRun(commitRun), fLine(0), fX(0) {}
+
+    void beginLine() override {
+        fX = 0;
+    }
+
+    void runInfo(const RunInfo&) override {}
+
+    void commitRunInfo() override {}
+
+    Buffer runBuffer(const RunInfo& info) override {
+        fGlyphs.resize(info.glyphCount);
+        fPositions.resize(info.glyphCount);
+        fClusters.resize(info.glyphCount);
+        return { fGlyphs.data(), fPositions.data(), nullptr, fClusters.data(), { fX, 0 } };
+    }
+
+    void commitRunBuffer(const RunInfo& info) override {
+        fCommitRun(fData,
+                   &info.fFont,
+                   info.fBidiLevel,
+                   &info.fAdvance,
+                   info.utf8Range.begin(), info.utf8Range.end(),
+                   fLine,
+                   info.glyphCount,
+                   fGlyphs.data(),
+                   fPositions.data(),
+                   fClusters.data());
+        fX += info.fAdvance.fX;
+    }
+
+    void commitLine() override {
+        fLine++;
+    }
+
+private:
+    void* fData;
+    RustRunHandler_CommitRun fCommitRun;
+    size_t fLine;
+    SkScalar fX;
+    std::vector<SkGlyphID> fGlyphs;
+    std::vector<SkPoint> fPositions;
+    std::vector<uint32_t> fClusters;
+};
+
+extern "C" void C_SkShaper_shapeRuns(
+        const SkShaper* self,
+        const char* utf8, size_t utf8Bytes,
+        const SkFont* font,
+        bool leftToRight,
+        SkScalar width,
+        void* data,
+        RustRunHandler_CommitRun commitRun) {
+    RustRunHandler handler(data, commitRun);
+    self->shape(utf8, utf8Bytes, *font, leftToRight, width, &handler);
+}
+
+#endif
@@ -1,4 +1,21 @@
-// TODO: wrap SkAndroidCodec.h, SkCodec.h, SkCodecAnimation.h
+mod _codec;
+pub use _codec::*;
+
+mod android_codec;
+pub use android_codec::*;
+
+mod codec_animation;
+pub use codec_animation::*;
 
 mod encoded_origin;
 pub use encoded_origin::*;
+
+#[cfg(test)]
+fn encoded_png(color: crate::Color) -> crate::Data {
+    let mut surface = crate::Surface::new_raster_n32_premul((16, 8)).unwrap();
+    surface.canvas().clear(color);
+    surface
+        .image_snapshot()
+        .encode_to_data(crate::EncodedImageFormat::PNG)
+        .unwrap()
+}
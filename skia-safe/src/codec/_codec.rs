@@ -0,0 +1,559 @@
+use crate::codec::DisposalMethod;
+use crate::interop::RustStream;
+use crate::prelude::*;
+use crate::{AlphaType, Data, EncodedImageFormat, EncodedOrigin, IRect, ISize, ImageInfo, Pixmap};
+use skia_bindings::{
+    C_SkCodec_MakeFromData, C_SkCodec_MakeFromStream, C_SkCodec_ResultToString, C_SkCodec_bounds,
+    C_SkCodec_delete, C_SkCodec_dimensions, C_SkCodec_getEncodedFormat, C_SkCodec_getFrameCount,
+    C_SkCodec_getFrameInfo, C_SkCodec_getInfo, C_SkCodec_getOrigin, C_SkCodec_getPixels,
+    C_SkCodec_getRepetitionCount, C_SkCodec_getScaledDimensions, C_SkCodec_getScanlineOrder,
+    C_SkCodec_getScanlines, C_SkCodec_getValidSubset, C_SkCodec_incrementalDecode,
+    C_SkCodec_nextScanline, C_SkCodec_outputScanline, C_SkCodec_skipScanlines,
+    C_SkCodec_startIncrementalDecode, C_SkCodec_startScanlineDecode, SkCodec, SkCodec_FrameInfo,
+    SkCodec_Options, SkCodec_Result, SkCodec_SkScanlineOrder, SkCodec_ZeroInitialized,
+};
+use std::ffi::CStr;
+use std::io::{Read, Seek};
+use std::marker::PhantomData;
+use std::{fmt, mem};
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[repr(i32)]
+pub enum Result {
+    Success = SkCodec_Result::kSuccess as _,
+    IncompleteInput = SkCodec_Result::kIncompleteInput as _,
+    ErrorInInput = SkCodec_Result::kErrorInInput as _,
+    InvalidConversion = SkCodec_Result::kInvalidConversion as _,
+    InvalidScale = SkCodec_Result::kInvalidScale as _,
+    InvalidParameters = SkCodec_Result::kInvalidParameters as _,
+    InvalidInput = SkCodec_Result::kInvalidInput as _,
+    CouldNotRewind = SkCodec_Result::kCouldNotRewind as _,
+    InternalError = SkCodec_Result::kInternalError as _,
+    Unimplemented = SkCodec_Result::kUnimplemented as _,
+}
+
+impl NativeTransmutable<SkCodec_Result> for Result {}
+#[test]
+fn test_result_layout() {
+    Result::test_layout()
+}
+
+impl fmt::Display for Result {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = unsafe { CStr::from_ptr(C_SkCodec_ResultToString(self.into_native())) };
+        f.write_str(&s.to_string_lossy())
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[repr(i32)]
+pub enum ZeroInitialized {
+    Yes = SkCodec_ZeroInitialized::kYes_ZeroInitialized as _,
+    No = SkCodec_ZeroInitialized::kNo_ZeroInitialized as _,
+}
+
+impl NativeTransmutable<SkCodec_ZeroInitialized> for ZeroInitialized {}
+#[test]
+fn test_zero_initialized_layout() {
+    ZeroInitialized::test_layout()
+}
+
+impl Default for ZeroInitialized {
+    fn default() -> Self {
+        ZeroInitialized::No
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[repr(i32)]
+pub enum ScanlineOrder {
+    TopDown = SkCodec_SkScanlineOrder::kTopDown_SkScanlineOrder as _,
+    BottomUp = SkCodec_SkScanlineOrder::kBottomUp_SkScanlineOrder as _,
+}
+
+impl NativeTransmutable<SkCodec_SkScanlineOrder> for ScanlineOrder {}
+#[test]
+fn test_scanline_order_layout() {
+    ScanlineOrder::test_layout()
+}
+
+#[derive(Clone, PartialEq, Eq, Default, Debug)]
+pub struct Options {
+    pub zero_initialized: ZeroInitialized,
+    pub subset: Option<IRect>,
+    /// The frame to decode. Only meaningful for images that contain multiple frames.
+    pub frame_index: usize,
+    /// A frame the destination already contains and that can be used as the base for
+    /// decoding `frame_index`. If `None`, the codec decodes all required frames itself.
+    pub prior_frame: Option<usize>,
+}
+
+impl Options {
+    // note: the native options refer to the subset of self, so the result
+    // must not outlive the options it was created from.
+    // Returns None if a frame index can not be represented natively.
+    pub(crate) fn native(&self) -> Option<SkCodec_Options> {
+        let prior_frame = match self.prior_frame {
+            Some(prior_frame) => prior_frame.try_into().ok()?,
+            None => Codec::NO_FRAME,
+        };
+        Some(SkCodec_Options {
+            fZeroInitialized: self.zero_initialized.into_native(),
+            fSubset: self.subset.native().as_ptr_or_null(),
+            fFrameIndex: self.frame_index.try_into().ok()?,
+            fPriorFrame: prior_frame,
+        })
+    }
+}
+
+/// Converts the optional options to their native representation, or returns
+/// `InvalidParameters` if they can not be represented.
+fn native_options(
+    options: Option<&Options>,
+) -> std::result::Result<Option<SkCodec_Options>, Result> {
+    match options {
+        Some(options) => options.native().map(Some).ok_or(Result::InvalidParameters),
+        None => Ok(None),
+    }
+}
+
+/// Information about a single frame of an animated image.
+///
+/// Skia m75 does not report the blend mode and the frame rect of a frame, so they are not
+/// available here.
+#[repr(C)]
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct FrameInfo {
+    /// The frame this frame needs to be drawn on top of, or `Codec::NO_FRAME` if it is
+    /// independent.
+    pub required_frame: i32,
+    /// Number of milliseconds to show this frame.
+    pub duration: i32,
+    /// Whether the end marker of this frame is contained in the stream.
+    pub fully_received: bool,
+    pub alpha_type: AlphaType,
+    pub disposal_method: DisposalMethod,
+}
+
+impl NativeTransmutable<SkCodec_FrameInfo> for FrameInfo {}
+#[test]
+fn test_frame_info_layout() {
+    FrameInfo::test_layout()
+}
+
+pub struct Codec {
+    native: *mut SkCodec,
+    // the minimum row bytes of the destination of the current scanline decode.
+    scanline_min_row_bytes: Option<usize>,
+}
+
+impl NativeAccess<SkCodec> for Codec {
+    fn native(&self) -> &SkCodec {
+        unsafe { &*self.native }
+    }
+
+    fn native_mut(&mut self) -> &mut SkCodec {
+        unsafe { &mut *self.native }
+    }
+}
+
+impl Drop for Codec {
+    fn drop(&mut self) {
+        unsafe { C_SkCodec_delete(self.native) }
+    }
+}
+
+impl Codec {
+    pub const NO_FRAME: i32 = -1;
+    pub const REPETITION_COUNT_INFINITE: i32 = -1;
+
+    fn from_native_ptr(native: *mut SkCodec) -> Option<Codec> {
+        native.to_option().map(|native| Codec {
+            native,
+            scanline_min_row_bytes: None,
+        })
+    }
+
+    pub fn from_data(data: &Data) -> Option<Codec> {
+        Self::from_native_ptr(unsafe { C_SkCodec_MakeFromData(data.shared_native()) })
+    }
+
+    /// Reads all the encoded bytes from `read` and creates a codec from them.
+    ///
+    /// Prefer `from_stream()` if the source is seekable, it avoids copying the encoded data.
+    pub fn from_read(mut read: impl Read) -> Option<Codec> {
+        let mut bytes = Vec::new();
+        read.read_to_end(&mut bytes).ok()?;
+        Self::from_data(&Data::new_copy(&bytes))
+    }
+
+    /// Creates a codec that pulls the encoded data from `stream`, starting at its current
+    /// position, as needed.
+    ///
+    /// The codec takes ownership of the stream. The stream can not be duplicated, so decoders
+    /// that read from a duplicate of their stream fail to decode the image.
+    pub fn from_stream(stream: impl Read + Seek + 'static) -> Option<Codec> {
+        let stream = RustStream::new(stream).ok()?;
+        Self::from_native_ptr(unsafe { C_SkCodec_MakeFromStream(stream.into_native()) })
+    }
+
+    pub fn info(&self) -> ImageInfo {
+        let mut info = ImageInfo::default();
+        unsafe { C_SkCodec_getInfo(self.native(), info.native_mut()) };
+        info
+    }
+
+    pub fn dimensions(&self) -> ISize {
+        let mut dimensions = ISize::default();
+        unsafe { C_SkCodec_dimensions(self.native(), dimensions.native_mut()) };
+        dimensions
+    }
+
+    pub fn bounds(&self) -> IRect {
+        let mut bounds = IRect::default();
+        unsafe { C_SkCodec_bounds(self.native(), bounds.native_mut()) };
+        bounds
+    }
+
+    pub fn origin(&self) -> EncodedOrigin {
+        EncodedOrigin::from_native(unsafe { C_SkCodec_getOrigin(self.native()) })
+    }
+
+    pub fn scaled_dimensions(&self, desired_scale: f32) -> ISize {
+        let mut dimensions = ISize::default();
+        unsafe {
+            C_SkCodec_getScaledDimensions(self.native(), desired_scale, dimensions.native_mut())
+        };
+        dimensions
+    }
+
+    pub fn valid_subset(&self, desired_subset: impl AsRef<IRect>) -> Option<IRect> {
+        let mut subset = *desired_subset.as_ref();
+        unsafe { C_SkCodec_getValidSubset(self.native(), subset.native_mut()) }.if_true_some(subset)
+    }
+
+    pub fn encoded_format(&self) -> EncodedImageFormat {
+        EncodedImageFormat::from_native(unsafe { C_SkCodec_getEncodedFormat(self.native()) })
+    }
+
+    /// Decodes the image into the pixels `dst` refers to.
+    ///
+    /// The image info of the pixmap may differ from `info()` in color type, alpha type and
+    /// color space, and may be scaled to one of the dimensions `scaled_dimensions()` returns.
+    pub fn get_pixels(&mut self, dst: &Pixmap, options: Option<&Options>) -> Result {
+        self.scanline_min_row_bytes = None;
+        let options = match native_options(options) {
+            Ok(options) => options,
+            Err(result) => return result,
+        };
+        Result::from_native(unsafe {
+            C_SkCodec_getPixels(
+                self.native_mut(),
+                dst.info().native(),
+                dst.writable_addr(),
+                dst.row_bytes(),
+                options.as_ptr_or_null(),
+            )
+        })
+    }
+
+    /// Prepares for an incremental decode into the pixels `dst` refers to.
+    ///
+    /// The returned decoder borrows the codec and the destination until the decode is complete.
+    pub fn start_incremental_decode<'a>(
+        &'a mut self,
+        dst: &'a Pixmap,
+        options: Option<&Options>,
+    ) -> std::result::Result<IncrementalDecoder<'a>, Result> {
+        self.scanline_min_row_bytes = None;
+        let options = native_options(options)?;
+        let result = Result::from_native(unsafe {
+            C_SkCodec_startIncrementalDecode(
+                self.native_mut(),
+                dst.info().native(),
+                dst.writable_addr(),
+                dst.row_bytes(),
+                options.as_ptr_or_null(),
+            )
+        });
+        match result {
+            Result::Success => Ok(IncrementalDecoder {
+                codec: self,
+                pd: PhantomData,
+            }),
+            error => Err(error),
+        }
+    }
+
+    /// Prepares for a scanline decode with the given destination info.
+    ///
+    /// Subsets in the options are supported only for their left and right edges.
+    pub fn start_scanline_decode(
+        &mut self,
+        dst_info: &ImageInfo,
+        options: Option<&Options>,
+    ) -> Result {
+        self.scanline_min_row_bytes = None;
+        let options = match native_options(options) {
+            Ok(options) => options,
+            Err(result) => return result,
+        };
+        let result = Result::from_native(unsafe {
+            C_SkCodec_startScanlineDecode(
+                self.native_mut(),
+                dst_info.native(),
+                options.as_ptr_or_null(),
+            )
+        });
+        self.scanline_min_row_bytes = match result {
+            Result::Success => Some(dst_info.min_row_bytes()),
+            _ => None,
+        };
+        result
+    }
+
+    /// Writes the next `count_lines` scanlines into `dst` and returns the number of lines
+    /// successfully decoded.
+    ///
+    /// `row_bytes` must be at least the minimum row bytes of the info the scanline decode was
+    /// started with. Returns 0 if no scanline decode was started.
+    pub fn get_scanlines(&mut self, dst: &mut [u8], count_lines: usize, row_bytes: usize) -> usize {
+        let min_row_bytes = match self.scanline_min_row_bytes {
+            Some(min_row_bytes) => min_row_bytes,
+            None => return 0,
+        };
+        assert!(row_bytes >= min_row_bytes);
+        assert!(dst.len() >= count_lines * row_bytes);
+        unsafe {
+            C_SkCodec_getScanlines(
+                self.native_mut(),
+                dst.as_mut_ptr() as _,
+                count_lines.try_into().unwrap(),
+                row_bytes,
+            )
+        }
+        .try_into()
+        .unwrap()
+    }
+
+    #[must_use]
+    pub fn skip_scanlines(&mut self, count_lines: usize) -> bool {
+        unsafe { C_SkCodec_skipScanlines(self.native_mut(), count_lines.try_into().unwrap()) }
+    }
+
+    pub fn scanline_order(&self) -> ScanlineOrder {
+        ScanlineOrder::from_native(unsafe { C_SkCodec_getScanlineOrder(self.native()) })
+    }
+
+    pub fn next_scanline(&self) -> i32 {
+        unsafe { C_SkCodec_nextScanline(self.native()) }
+    }
+
+    pub fn output_scanline(&self, input_scanline: i32) -> i32 {
+        unsafe { C_SkCodec_outputScanline(self.native(), input_scanline) }
+    }
+
+    /// The number of frames in the image.
+    ///
+    /// May require reading through the stream, and may change if more data arrives.
+    pub fn frame_count(&mut self) -> usize {
+        unsafe { C_SkCodec_getFrameCount(self.native_mut()) }
+            .try_into()
+            .unwrap()
+    }
+
+    /// Returns information about a single frame, or `None` if the image is not animated or
+    /// the frame is not available yet.
+    pub fn frame_info(&self, index: usize) -> Option<FrameInfo> {
+        let index = index.try_into().ok()?;
+        let mut info = unsafe { mem::zeroed() };
+        unsafe { C_SkCodec_getFrameInfo(self.native(), index, &mut info) }
+            .if_true_then_some(|| FrameInfo::from_native(info))
+    }
+
+    /// Returns information about all frames of an animated image.
+    pub fn frame_infos(&mut self) -> Vec<FrameInfo> {
+        (0..self.frame_count())
+            .filter_map(|index| self.frame_info(index))
+            .collect()
+    }
+
+    /// The number of times to repeat the animation after the first play through, or
+    /// `REPETITION_COUNT_INFINITE` if the animation repeats forever.
+    pub fn repetition_count(&mut self) -> i32 {
+        unsafe { C_SkCodec_getRepetitionCount(self.native_mut()) }
+    }
+}
+
+/// An incremental decode in progress, created by `Codec::start_incremental_decode()`.
+pub struct IncrementalDecoder<'a> {
+    codec: &'a mut Codec,
+    pd: PhantomData<&'a Pixmap>,
+}
+
+impl<'a> IncrementalDecoder<'a> {
+    /// Decodes as much of the image as the available input allows.
+    ///
+    /// Returns `Success` if the image is complete. If the result is `IncompleteInput`, the
+    /// number of rows that were initialized is returned, too, and `decode()` can be called again
+    /// as soon more data is available.
+    pub fn decode(&mut self) -> (Result, Option<usize>) {
+        let mut rows_decoded = 0;
+        let result = Result::from_native(unsafe {
+            C_SkCodec_incrementalDecode(self.codec.native_mut(), &mut rows_decoded)
+        });
+        match result {
+            Result::IncompleteInput => (result, Some(rows_decoded.try_into().unwrap())),
+            _ => (result, None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Codec, Options, Result, ZeroInitialized};
+    use crate::codec::{encoded_png, DisposalMethod};
+    use crate::{Color, EncodedImageFormat, IRect, ISize, ImageInfo, Pixmap};
+
+    #[test]
+    fn decode_info_and_pixels() {
+        let mut codec = Codec::from_data(&encoded_png(Color::RED)).unwrap();
+        assert_eq!(EncodedImageFormat::PNG, codec.encoded_format());
+        assert_eq!(ISize::new(16, 8), codec.dimensions());
+
+        let info = ImageInfo::new_n32_premul(codec.dimensions(), None);
+        let pixels = vec![0u8; info.compute_min_byte_size()];
+        let pixmap = Pixmap::new(&info, &pixels, info.min_row_bytes());
+        assert_eq!(Result::Success, codec.get_pixels(&pixmap, None));
+        assert_eq!(Color::RED, pixmap.get_color((15, 7)));
+    }
+
+    #[test]
+    fn decode_incrementally() {
+        let mut codec = Codec::from_read(encoded_png(Color::RED).as_bytes()).unwrap();
+        let info = ImageInfo::new_n32_premul(codec.dimensions(), None);
+        let pixels = vec![0u8; info.compute_min_byte_size()];
+        let pixmap = Pixmap::new(&info, &pixels, info.min_row_bytes());
+        let options = Options {
+            zero_initialized: ZeroInitialized::Yes,
+            ..Options::default()
+        };
+        let mut decoder = codec
+            .start_incremental_decode(&pixmap, Some(&options))
+            .unwrap();
+        assert_eq!((Result::Success, None), decoder.decode());
+        assert_eq!(Color::RED, pixmap.get_color((0, 0)));
+    }
+
+    #[test]
+    fn decode_from_stream() {
+        let stream = std::io::Cursor::new(encoded_png(Color::RED).as_bytes().to_vec());
+        let mut codec = Codec::from_stream(stream).unwrap();
+        let info = ImageInfo::new_n32_premul(codec.dimensions(), None);
+        let pixels = vec![0u8; info.compute_min_byte_size()];
+        let pixmap = Pixmap::new(&info, &pixels, info.min_row_bytes());
+        assert_eq!(Result::Success, codec.get_pixels(&pixmap, None));
+        assert_eq!(Color::RED, pixmap.get_color((8, 4)));
+    }
+
+    #[test]
+    fn decode_scanlines() {
+        let mut codec = Codec::from_data(&encoded_png(Color::RED)).unwrap();
+        let info = ImageInfo::new_n32_premul(codec.dimensions(), None);
+        let row_bytes = info.min_row_bytes();
+        assert_eq!(Result::Success, codec.start_scanline_decode(&info, None));
+        assert!(codec.skip_scanlines(4));
+        let mut lines = vec![0u8; row_bytes * 4];
+        assert_eq!(4, codec.get_scanlines(&mut lines, 4, row_bytes));
+        assert_eq!(8, codec.next_scanline());
+    }
+
+    #[test]
+    #[should_panic]
+    fn get_scanlines_with_too_small_row_bytes_panics() {
+        let mut codec = Codec::from_data(&encoded_png(Color::RED)).unwrap();
+        let info = ImageInfo::new_n32_premul(codec.dimensions(), None);
+        assert_eq!(Result::Success, codec.start_scanline_decode(&info, None));
+        let mut lines = vec![0u8; info.min_row_bytes() * 2];
+        codec.get_scanlines(&mut lines, 4, info.min_row_bytes() / 2);
+    }
+
+    #[test]
+    fn get_scanlines_without_scanline_decode_decodes_nothing() {
+        let mut codec = Codec::from_data(&encoded_png(Color::RED)).unwrap();
+        let mut lines = vec![0u8; 16];
+        assert_eq!(0, codec.get_scanlines(&mut lines, 4, 4));
+    }
+
+    #[test]
+    fn valid_subset_of_png_is_not_supported() {
+        let codec = Codec::from_data(&encoded_png(Color::RED)).unwrap();
+        assert_eq!(None, codec.valid_subset(IRect::from_wh(4, 4)));
+    }
+
+    #[test]
+    fn still_image_has_one_frame_and_no_frame_infos() {
+        let mut codec = Codec::from_data(&encoded_png(Color::RED)).unwrap();
+        assert_eq!(1, codec.frame_count());
+        assert_eq!(None, codec.frame_info(0));
+        assert!(codec.frame_infos().is_empty());
+        assert_eq!(0, codec.repetition_count());
+    }
+
+    // a 1x1 GIF with a red and a blue frame that are shown for 100 and 200 milliseconds and
+    // repeat forever.
+    #[rustfmt::skip]
+    const ANIMATED_GIF: &[u8] = &[
+        b'G', b'I', b'F', b'8', b'9', b'a', 0x01, 0x00, 0x01, 0x00, 0x80, 0x00, 0x00,
+        0xff, 0x00, 0x00, 0x00, 0x00, 0xff,
+        0x21, 0xff, 0x0b, b'N', b'E', b'T', b'S', b'C', b'A', b'P', b'E', b'2', b'.', b'0',
+        0x03, 0x01, 0x00, 0x00, 0x00,
+        0x21, 0xf9, 0x04, 0x04, 0x0a, 0x00, 0x00, 0x00,
+        0x2c, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x01, 0x00, 0x00,
+        0x02, 0x02, 0x44, 0x01, 0x00,
+        0x21, 0xf9, 0x04, 0x04, 0x14, 0x00, 0x00, 0x00,
+        0x2c, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x01, 0x00, 0x00,
+        0x02, 0x02, 0x4c, 0x01, 0x00,
+        0x3b,
+    ];
+
+    #[test]
+    fn animated_gif_frames() {
+        let mut codec = Codec::from_data(&crate::Data::new_copy(ANIMATED_GIF)).unwrap();
+        assert_eq!(EncodedImageFormat::GIF, codec.encoded_format());
+        assert_eq!(2, codec.frame_count());
+        assert_eq!(Codec::REPETITION_COUNT_INFINITE, codec.repetition_count());
+
+        let frame_infos = codec.frame_infos();
+        assert_eq!(2, frame_infos.len());
+        assert_eq!(100, frame_infos[0].duration);
+        assert_eq!(200, frame_infos[1].duration);
+        assert!(frame_infos.iter().all(|f| f.fully_received));
+        assert!(frame_infos
+            .iter()
+            .all(|f| f.disposal_method == DisposalMethod::Keep));
+        assert_eq!(None, codec.frame_info(2));
+        assert_eq!(None, codec.frame_info(usize::max_value()));
+
+        let info = ImageInfo::new_n32_premul(codec.dimensions(), None);
+        let pixels = vec![0u8; info.compute_min_byte_size()];
+        let pixmap = Pixmap::new(&info, &pixels, info.min_row_bytes());
+        for (frame_index, color) in [Color::RED, Color::BLUE].iter().enumerate() {
+            let options = Options {
+                frame_index,
+                ..Options::default()
+            };
+            assert_eq!(Result::Success, codec.get_pixels(&pixmap, Some(&options)));
+            assert_eq!(*color, pixmap.get_color((0, 0)));
+        }
+
+        let options = Options {
+            frame_index: usize::max_value(),
+            ..Options::default()
+        };
+        assert_eq!(
+            Result::InvalidParameters,
+            codec.get_pixels(&pixmap, Some(&options))
+        );
+    }
+}
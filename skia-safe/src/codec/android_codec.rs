@@ -0,0 +1,210 @@
+use crate::codec::{Result, ZeroInitialized};
+use crate::prelude::*;
+use crate::{
+    AlphaType, Codec, ColorSpace, ColorType, Data, EncodedImageFormat, IRect, ISize, ImageInfo,
+    Pixmap,
+};
+use skia_bindings::{
+    C_SkAndroidCodec_MakeFromCodec, C_SkAndroidCodec_MakeFromData,
+    C_SkAndroidCodec_computeOutputAlphaType, C_SkAndroidCodec_computeOutputColorSpace,
+    C_SkAndroidCodec_computeOutputColorType, C_SkAndroidCodec_computeSampleSize,
+    C_SkAndroidCodec_delete, C_SkAndroidCodec_getAndroidPixels, C_SkAndroidCodec_getEncodedFormat,
+    C_SkAndroidCodec_getInfo, C_SkAndroidCodec_getSampledDimensions,
+    C_SkAndroidCodec_getSampledSubsetDimensions, C_SkAndroidCodec_getSupportedSubset,
+    SkAndroidCodec,
+};
+use std::mem;
+
+/// Options for decoding with an `AndroidCodec`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct AndroidOptions {
+    pub zero_initialized: ZeroInitialized,
+    /// The subset to decode, must be supported, see `AndroidCodec::supported_subset()`.
+    pub subset: Option<IRect>,
+    /// Decodes every `sample_size`th pixel in both dimensions.
+    pub sample_size: usize,
+}
+
+impl Default for AndroidOptions {
+    fn default() -> Self {
+        AndroidOptions {
+            zero_initialized: ZeroInitialized::No,
+            subset: None,
+            sample_size: 1,
+        }
+    }
+}
+
+/// A codec that supports decoding downscaled images and subsets of images.
+pub struct AndroidCodec(*mut SkAndroidCodec);
+
+impl NativeAccess<SkAndroidCodec> for AndroidCodec {
+    fn native(&self) -> &SkAndroidCodec {
+        unsafe { &*self.0 }
+    }
+
+    fn native_mut(&mut self) -> &mut SkAndroidCodec {
+        unsafe { &mut *self.0 }
+    }
+}
+
+impl Drop for AndroidCodec {
+    fn drop(&mut self) {
+        unsafe { C_SkAndroidCodec_delete(self.0) }
+    }
+}
+
+impl AndroidCodec {
+    pub fn from_data(data: &Data) -> Option<AndroidCodec> {
+        unsafe { C_SkAndroidCodec_MakeFromData(data.shared_native()) }
+            .to_option()
+            .map(AndroidCodec)
+    }
+
+    pub fn from_codec(mut codec: Codec) -> Option<AndroidCodec> {
+        let android_codec = unsafe { C_SkAndroidCodec_MakeFromCodec(codec.native_mut()) };
+        mem::forget(codec);
+        android_codec.to_option().map(AndroidCodec)
+    }
+
+    pub fn info(&self) -> ImageInfo {
+        let mut info = ImageInfo::default();
+        unsafe { C_SkAndroidCodec_getInfo(self.native(), info.native_mut()) };
+        info
+    }
+
+    pub fn encoded_format(&self) -> EncodedImageFormat {
+        EncodedImageFormat::from_native(unsafe { C_SkAndroidCodec_getEncodedFormat(self.native()) })
+    }
+
+    pub fn compute_output_color_type(&mut self, requested_color_type: ColorType) -> ColorType {
+        ColorType::from_native(unsafe {
+            C_SkAndroidCodec_computeOutputColorType(
+                self.native_mut(),
+                requested_color_type.into_native(),
+            )
+        })
+    }
+
+    pub fn compute_output_alpha_type(&mut self, requested_unpremul: bool) -> AlphaType {
+        AlphaType::from_native(unsafe {
+            C_SkAndroidCodec_computeOutputAlphaType(self.native_mut(), requested_unpremul)
+        })
+    }
+
+    pub fn compute_output_color_space(
+        &mut self,
+        output_color_type: ColorType,
+        pref_color_space: Option<&ColorSpace>,
+    ) -> Option<ColorSpace> {
+        ColorSpace::from_ptr(unsafe {
+            C_SkAndroidCodec_computeOutputColorSpace(
+                self.native_mut(),
+                output_color_type.into_native(),
+                pref_color_space.shared_ptr(),
+            )
+        })
+    }
+
+    /// Returns the sample size that is needed to decode to the desired size, which is adjusted
+    /// to the dimensions that decoding with this sample size produces.
+    pub fn compute_sample_size(&self, size: &mut ISize) -> usize {
+        unsafe { C_SkAndroidCodec_computeSampleSize(self.native(), size.native_mut()) }
+            .try_into()
+            .unwrap()
+    }
+
+    /// Returns the dimensions of the image after decoding with the given sample size.
+    pub fn sampled_dimensions(&self, sample_size: usize) -> ISize {
+        let mut dimensions = ISize::default();
+        unsafe {
+            C_SkAndroidCodec_getSampledDimensions(
+                self.native(),
+                sample_size.try_into().unwrap(),
+                dimensions.native_mut(),
+            )
+        };
+        dimensions
+    }
+
+    /// Returns the closest subset to `desired_subset` that can be decoded, or `None` if the
+    /// subset is invalid or subset decoding is not supported.
+    pub fn supported_subset(&self, desired_subset: impl AsRef<IRect>) -> Option<IRect> {
+        let mut subset = *desired_subset.as_ref();
+        unsafe { C_SkAndroidCodec_getSupportedSubset(self.native(), subset.native_mut()) }
+            .if_true_some(subset)
+    }
+
+    /// Returns the dimensions of the subset after decoding with the given sample size.
+    pub fn sampled_subset_dimensions(
+        &self,
+        sample_size: usize,
+        subset: impl AsRef<IRect>,
+    ) -> ISize {
+        let mut dimensions = ISize::default();
+        unsafe {
+            C_SkAndroidCodec_getSampledSubsetDimensions(
+                self.native(),
+                sample_size.try_into().unwrap(),
+                subset.as_ref().native(),
+                dimensions.native_mut(),
+            )
+        };
+        dimensions
+    }
+
+    /// Decodes the image into the pixels `dst` refers to.
+    ///
+    /// The dimensions of the pixmap must match the dimensions the sample size and the subset
+    /// of the options produce.
+    pub fn get_android_pixels(&mut self, dst: &Pixmap, options: Option<&AndroidOptions>) -> Result {
+        let default_options = AndroidOptions::default();
+        let options = options.unwrap_or(&default_options);
+        Result::from_native(unsafe {
+            C_SkAndroidCodec_getAndroidPixels(
+                self.native_mut(),
+                dst.info().native(),
+                dst.writable_addr(),
+                dst.row_bytes(),
+                options.zero_initialized.into_native(),
+                options.subset.native().as_ptr_or_null(),
+                options.sample_size.try_into().unwrap(),
+            )
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AndroidCodec, AndroidOptions};
+    use crate::codec::{encoded_png, Result};
+    use crate::{Codec, Color, IRect, ISize, ImageInfo, Pixmap};
+
+    #[test]
+    fn decode_downsampled() {
+        let mut codec = AndroidCodec::from_data(&encoded_png(Color::BLUE)).unwrap();
+        let dimensions = codec.sampled_dimensions(2);
+        assert_eq!(ISize::new(8, 4), dimensions);
+
+        let info = ImageInfo::new_n32_premul(dimensions, None);
+        let pixels = vec![0u8; info.compute_min_byte_size()];
+        let pixmap = Pixmap::new(&info, &pixels, info.min_row_bytes());
+        let options = AndroidOptions {
+            sample_size: 2,
+            ..AndroidOptions::default()
+        };
+        assert_eq!(
+            Result::Success,
+            codec.get_android_pixels(&pixmap, Some(&options))
+        );
+        assert_eq!(Color::BLUE, pixmap.get_color((7, 3)));
+    }
+
+    #[test]
+    fn from_codec_and_invalid_subset() {
+        let codec = Codec::from_data(&encoded_png(Color::BLUE)).unwrap();
+        let codec = AndroidCodec::from_codec(codec).unwrap();
+        assert_eq!(ISize::new(16, 8), codec.info().dimensions());
+        assert_eq!(None, codec.supported_subset(IRect::from_wh(32, 32)));
+    }
+}
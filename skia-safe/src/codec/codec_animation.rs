@@ -0,0 +1,17 @@
+use crate::prelude::*;
+use skia_bindings::SkCodecAnimation_DisposalMethod;
+
+/// How the previous frame should be handled before the next one is drawn.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[repr(i32)]
+pub enum DisposalMethod {
+    Keep = SkCodecAnimation_DisposalMethod::kKeep as _,
+    RestoreBGColor = SkCodecAnimation_DisposalMethod::kRestoreBGColor as _,
+    RestorePrevious = SkCodecAnimation_DisposalMethod::kRestorePrevious as _,
+}
+
+impl NativeTransmutable<SkCodecAnimation_DisposalMethod> for DisposalMethod {}
+#[test]
+fn test_disposal_method_layout() {
+    DisposalMethod::test_layout()
+}
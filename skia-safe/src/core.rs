@@ -67,7 +67,7 @@ mod deferred_display_list_recorder;
 pub use deferred_display_list_recorder::*;
 
 pub mod document;
-pub use document::Document;
+pub use document::{Document, WriterDocument};
 
 pub mod draw_looper;
 #[deprecated(since = "0.12.0", note = "use draw_looper::BlurShadowRec")]
@@ -266,6 +266,9 @@ pub use rsxform::*;
 mod scalar_;
 pub use scalar_::*;
 
+mod serial_procs;
+pub use serial_procs::*;
+
 pub mod shader;
 #[deprecated(since = "0.12.0", note = "use shader::GradientInfo")]
 pub use shader::GradientInfo as ShaderGradientInfo;
@@ -324,6 +327,9 @@ pub use typeface::LocalizedString as TypefaceLocalizedString;
 pub use typeface::SerializeBehavior as TypefaceSerializeBehavior;
 pub use typeface::Typeface;
 
+mod typeface_font_provider;
+pub use typeface_font_provider::*;
+
 mod types;
 pub use types::*;
 
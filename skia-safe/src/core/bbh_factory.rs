@@ -1,14 +1,18 @@
 use crate::prelude::*;
-use skia_bindings::SkBBHFactory;
+use skia_bindings::{C_SkBBHFactory_destruct, C_SkRTreeFactory_construct, SkBBHFactory};
 
-// TODO: complete the implementation
 pub type BBHFactory = Handle<SkBBHFactory>;
 
 impl NativeDrop for SkBBHFactory {
     fn drop(&mut self) {
-        unimplemented!()
+        unsafe { C_SkBBHFactory_destruct(self) }
     }
 }
 
-// TODO: complete the implementation
-impl Handle<SkBBHFactory> {}
+impl Handle<SkBBHFactory> {
+    /// A factory for R-trees, which let the playback of a recorded picture skip the ops that
+    /// are outside of the canvas' clip.
+    pub fn new_rtree() -> BBHFactory {
+        Self::construct_c(C_SkRTreeFactory_construct)
+    }
+}
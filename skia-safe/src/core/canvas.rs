@@ -2,12 +2,12 @@ use crate::prelude::*;
 use crate::{gpu, Drawable, Pixmap};
 use crate::{
     scalar, vertices, Bitmap, BlendMode, ClipOp, Color, Data, Font, IPoint, IRect, ISize, Image,
-    ImageFilter, ImageInfo, Matrix, Paint, Path, Picture, Point, QuickReject, RRect, Rect, Region,
-    Surface, SurfaceProps, TextBlob, TextEncoding, Vector, Vertices,
+    ImageFilter, ImageInfo, Matrix, Paint, Path, Picture, Point, QuickReject, RRect, RSXform, Rect,
+    Region, Surface, SurfaceProps, TextBlob, TextEncoding, Vector, Vertices,
 };
 use skia_bindings::{
     C_SkAutoCanvasRestore_Construct, C_SkAutoCanvasRestore_destruct, C_SkAutoCanvasRestore_restore,
-    C_SkCanvas_MakeRasterDirect, C_SkCanvas_delete, C_SkCanvas_discard,
+    C_SkCanvas_MakeRasterDirect, C_SkCanvas_delete, C_SkCanvas_discard, C_SkCanvas_drawAtlas,
     C_SkCanvas_getBaseLayerSize, C_SkCanvas_getGrContext, C_SkCanvas_imageInfo,
     C_SkCanvas_isClipEmpty, C_SkCanvas_makeSurface, C_SkCanvas_newEmpty, C_SkCanvas_newFromBitmap,
     C_SkCanvas_newFromBitmapAndProps, C_SkCanvas_newWidthHeightAndProps, SkAutoCanvasRestore,
@@ -1003,7 +1003,37 @@ impl Canvas {
         self
     }
 
-    // TODO: drawAtlas
+    #[allow(clippy::too_many_arguments)]
+    pub fn draw_atlas(
+        &mut self,
+        atlas: &Image,
+        xform: &[RSXform],
+        tex: &[Rect],
+        colors: Option<&[Color]>,
+        mode: impl Into<Option<BlendMode>>,
+        cull_rect: Option<&Rect>,
+        paint: Option<&Paint>,
+    ) -> &mut Self {
+        let count = xform.len();
+        assert_eq!(tex.len(), count);
+        if let Some(colors) = colors {
+            assert_eq!(colors.len(), count);
+        }
+        unsafe {
+            C_SkCanvas_drawAtlas(
+                self.native_mut(),
+                atlas.native(),
+                xform.native().as_ptr(),
+                tex.native().as_ptr(),
+                colors.native().as_ptr_or_null(),
+                count.try_into().unwrap(),
+                mode.into().unwrap_or(BlendMode::Modulate).into_native(),
+                cull_rect.native_ptr_or_null(),
+                paint.native_ptr_or_null(),
+            )
+        }
+        self
+    }
 
     pub fn draw_drawable(&mut self, drawable: &mut Drawable, matrix: Option<&Matrix>) {
         unsafe {
@@ -1226,8 +1256,8 @@ impl AutoCanvasRestore {
 #[cfg(test)]
 mod tests {
     use crate::{
-        AlphaType, Canvas, ClipOp, Color, ColorType, ImageInfo, Matrix, OwnedCanvas, Rect,
-        SaveLayerRec,
+        AlphaType, Canvas, ClipOp, Color, ColorType, ImageInfo, Matrix, OwnedCanvas,
+        PictureRecorder, RSXform, Rect, SaveLayerRec, Surface,
     };
 
     #[test]
@@ -1308,4 +1338,55 @@ mod tests {
         // both
         c.clip_rect(Rect::default(), ClipOp::Difference, true);
     }
+
+    #[test]
+    fn draw_atlas_is_recorded_and_played_back() {
+        let mut atlas_surface = Surface::new_raster_n32_premul((4, 4)).unwrap();
+        atlas_surface.canvas().clear(Color::RED);
+        let atlas = atlas_surface.image_snapshot();
+
+        let xforms = [
+            RSXform::new(1.0, 0.0, (0.0, 0.0)),
+            RSXform::new(1.0, 0.0, (4.0, 4.0)),
+        ];
+        let tex = [Rect::from_wh(4.0, 4.0), Rect::from_wh(4.0, 4.0)];
+
+        let mut recorder = PictureRecorder::new();
+        let bounds = Rect::from_wh(8.0, 8.0);
+        recorder
+            .begin_recording(&bounds, None, None)
+            .draw_atlas(&atlas, &xforms, &tex, None, None, None, None);
+        let picture = recorder.finish_recording_as_picture(None).unwrap();
+        assert_eq!(picture.approximate_op_count(), 1);
+
+        let mut surface = Surface::new_raster_n32_premul((8, 8)).unwrap();
+        surface.canvas().clear(Color::WHITE);
+        picture.playback(surface.canvas());
+
+        let info = ImageInfo::new_n32_premul((8, 8), None);
+        let mut pixels = vec![0u8; 8 * 8 * 4];
+        assert!(surface.read_pixels(&info, pixels.as_mut_slice(), 8 * 4, (0, 0)));
+        let pixel = |x: usize, y: usize| &pixels[(y * 8 + x) * 4..][..4];
+        // the sprites cover the top left and the bottom right quadrant.
+        assert_eq!(pixel(7, 7), pixel(0, 0));
+        assert_ne!(pixel(7, 0), pixel(0, 0));
+        assert_ne!(pixel(0, 7), pixel(0, 0));
+    }
+
+    #[test]
+    #[should_panic]
+    fn draw_atlas_panics_on_mismatched_slices() {
+        let mut atlas_surface = Surface::new_raster_n32_premul((4, 4)).unwrap();
+        let atlas = atlas_surface.image_snapshot();
+        let mut c = OwnedCanvas::default();
+        c.draw_atlas(
+            &atlas,
+            &[RSXform::new(1.0, 0.0, (0.0, 0.0))],
+            &[],
+            None,
+            None,
+            None,
+            None,
+        );
+    }
 }
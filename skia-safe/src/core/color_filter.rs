@@ -2,9 +2,10 @@ use crate::prelude::*;
 use crate::{scalar, BlendMode, Color, Color4f, ColorSpace, NativeFlattenable};
 use skia_bindings::{
     C_SkColorFilter_Deserialize, C_SkColorFilter_asColorMatrix, C_SkColorFilter_asColorMode,
-    C_SkColorFilter_getFlags, C_SkColorFilter_makeComposed, SkColorFilter,
+    C_SkColorFilter_getFlags, C_SkColorFilter_makeComposed, RustDeserialProcs, SkColorFilter,
     SkColorFilter_Flags_kAlphaUnchanged_Flag, SkFlattenable, SkRefCntBase,
 };
+use std::ptr;
 
 bitflags! {
     pub struct Flags: u32 {
@@ -27,7 +28,11 @@ impl NativeFlattenable for SkColorFilter {
     }
 
     fn native_deserialize(data: &[u8]) -> *mut Self {
-        unsafe { C_SkColorFilter_Deserialize(data.as_ptr() as _, data.len()) }
+        Self::native_deserialize_with_procs(data, ptr::null())
+    }
+
+    fn native_deserialize_with_procs(data: &[u8], procs: *const RustDeserialProcs) -> *mut Self {
+        unsafe { C_SkColorFilter_Deserialize(data.as_ptr() as _, data.len(), procs) }
     }
 }
 
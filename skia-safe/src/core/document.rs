@@ -1,18 +1,20 @@
-use crate::interop::DynamicMemoryWStream;
 use crate::prelude::*;
 use crate::{Canvas, Data, Rect, Size};
 use skia_bindings::{SkDocument, SkRefCntBase};
-use std::pin::Pin;
+use std::io;
 
-pub struct Document<State = state::Open> {
+pub struct Document<State = state::Open, Output = output::Memory> {
     // note: order matters here, first the document must be
-    // dropped _and then_ the stream.
+    // dropped _and then_ the output stream.
     document: RCHandle<SkDocument>,
-    stream: Pin<Box<DynamicMemoryWStream>>,
+    output: Output,
 
     state: State,
 }
 
+/// A document that streams its output to a Rust writer.
+pub type WriterDocument<'a, State = state::Open> = Document<State, output::Writer<'a>>;
+
 impl NativeRefCountedBase for SkDocument {
     type Base = SkRefCntBase;
 
@@ -36,21 +38,62 @@ pub mod state {
     }
 }
 
-impl<S> Document<S> {
+/// The outputs documents and SVG canvases can be written to.
+pub mod output {
+    use crate::interop::{DynamicMemoryWStream, RustWStream};
+    use skia_bindings::SkWStream;
+    use std::io::Write;
+    use std::pin::Pin;
+
+    /// The output is written to memory and returned when the document is closed.
+    pub struct Memory(pub(crate) Pin<Box<DynamicMemoryWStream>>);
+
+    /// The output is streamed to a Rust writer.
+    pub struct Writer<'a>(pub(crate) RustWStream<'a>);
+
+    pub(crate) trait OutputStream {
+        fn stream_mut(&mut self) -> &mut SkWStream;
+    }
+
+    impl Memory {
+        pub(crate) fn new() -> Memory {
+            // the stream can't be moved around anymore as soon it's referred to by a native
+            // object.
+            Memory(Box::pin(DynamicMemoryWStream::new()))
+        }
+    }
+
+    impl OutputStream for Memory {
+        fn stream_mut(&mut self) -> &mut SkWStream {
+            &mut self.0.native_mut()._base
+        }
+    }
+
+    impl<'a> Writer<'a> {
+        pub(crate) fn new(writer: impl Write + 'a) -> Writer<'a> {
+            Writer(RustWStream::new(writer))
+        }
+    }
+
+    impl<'a> OutputStream for Writer<'a> {
+        fn stream_mut(&mut self) -> &mut SkWStream {
+            self.0.stream_mut()
+        }
+    }
+}
+
+impl<S, O> Document<S, O> {
     pub fn abort(mut self) {
         unsafe { self.document.native_mut().abort() }
         drop(self)
     }
 }
 
-impl Document {
-    pub(crate) fn new(
-        stream: Pin<Box<DynamicMemoryWStream>>,
-        document: RCHandle<SkDocument>,
-    ) -> Self {
+impl<O> Document<state::Open, O> {
+    pub(crate) fn new(output: O, document: RCHandle<SkDocument>) -> Self {
         Document {
             document,
-            stream,
+            output,
             state: state::Open { pages: 0 },
         }
     }
@@ -66,7 +109,7 @@ impl Document {
         mut self,
         size: impl Into<Size>,
         content: Option<&Rect>,
-    ) -> Document<state::OnPage> {
+    ) -> Document<state::OnPage, O> {
         let size = size.into();
         let canvas = unsafe {
             self.document.native_mut().beginPage(
@@ -77,7 +120,7 @@ impl Document {
         };
 
         Document {
-            stream: self.stream,
+            output: self.output,
             document: self.document,
             state: state::OnPage {
                 canvas,
@@ -85,18 +128,32 @@ impl Document {
             },
         } as _
     }
+}
 
+impl Document {
     /// Close the document and return the encoded representation.
     /// This function consumes and drops the document.
     pub fn close(mut self) -> Data {
         unsafe {
             self.document.native_mut().close();
         };
-        self.stream.detach_as_data()
+        self.output.0.detach_as_data()
+    }
+}
+
+impl<'a> WriterDocument<'a> {
+    /// Close the document and flush the writer. Returns the first I/O error that occurred
+    /// while the document was written.
+    /// This function consumes and drops the document.
+    pub fn close(mut self) -> io::Result<()> {
+        unsafe {
+            self.document.native_mut().close();
+        };
+        self.output.0.finish()
     }
 }
 
-impl Document<state::OnPage> {
+impl<O> Document<state::OnPage, O> {
     /// The current page we are currently drawing on.
     pub fn page(&self) -> usize {
         self.state.page
@@ -110,13 +167,13 @@ impl Document<state::OnPage> {
     /// Ends the page.
     /// This function consumes the document and returns a new open document that
     /// contains the pages drawn so far.
-    pub fn end_page(mut self) -> Document {
+    pub fn end_page(mut self) -> Document<state::Open, O> {
         unsafe {
             self.document.native_mut().endPage();
         }
 
         Document {
-            stream: self.stream,
+            output: self.output,
             document: self.document,
             state: state::Open {
                 pages: self.state.page,
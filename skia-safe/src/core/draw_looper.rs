@@ -1,9 +1,10 @@
 use crate::prelude::*;
 use crate::{scalar, BlurStyle, Color, NativeFlattenable, Paint, Rect, Vector};
 use skia_bindings::{
-    C_SkDrawLooper_Deserialize, C_SkDrawLooper_asABlurShadow, SkDrawLooper,
+    C_SkDrawLooper_Deserialize, C_SkDrawLooper_asABlurShadow, RustDeserialProcs, SkDrawLooper,
     SkDrawLooper_BlurShadowRec, SkFlattenable, SkRefCntBase,
 };
+use std::ptr;
 
 pub type DrawLooper = RCHandle<SkDrawLooper>;
 
@@ -21,7 +22,11 @@ impl NativeFlattenable for SkDrawLooper {
     }
 
     fn native_deserialize(data: &[u8]) -> *mut Self {
-        unsafe { C_SkDrawLooper_Deserialize(data.as_ptr() as _, data.len()) }
+        Self::native_deserialize_with_procs(data, ptr::null())
+    }
+
+    fn native_deserialize_with_procs(data: &[u8], procs: *const RustDeserialProcs) -> *mut Self {
+        unsafe { C_SkDrawLooper_Deserialize(data.as_ptr() as _, data.len(), procs) }
     }
 }
 
@@ -2,9 +2,10 @@ use crate::prelude::*;
 use crate::{gpu, Canvas, IRect, ImageInfo, Matrix, NativeFlattenable, Point, Rect};
 use skia_bindings::{
     C_SkDrawable_Deserialize, C_SkDrawable_GpuDrawHandler_destruct,
-    C_SkDrawable_GpuDrawHandler_draw, C_SkDrawable_snapGpuDrawHandler, SkDrawable,
-    SkDrawable_GpuDrawHandler, SkFlattenable, SkRefCntBase,
+    C_SkDrawable_GpuDrawHandler_draw, C_SkDrawable_snapGpuDrawHandler, RustDeserialProcs,
+    SkDrawable, SkDrawable_GpuDrawHandler, SkFlattenable, SkRefCntBase,
 };
+use std::ptr;
 
 pub type Drawable = RCHandle<SkDrawable>;
 
@@ -22,7 +23,11 @@ impl NativeFlattenable for SkDrawable {
     }
 
     fn native_deserialize(data: &[u8]) -> *mut Self {
-        unsafe { C_SkDrawable_Deserialize(data.as_ptr() as _, data.len()) }
+        Self::native_deserialize_with_procs(data, ptr::null())
+    }
+
+    fn native_deserialize_with_procs(data: &[u8], procs: *const RustDeserialProcs) -> *mut Self {
+        unsafe { C_SkDrawable_Deserialize(data.as_ptr() as _, data.len(), procs) }
     }
 }
 
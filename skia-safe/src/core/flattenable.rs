@@ -1,25 +1,42 @@
 use crate::prelude::*;
-use crate::Data;
-use skia_bindings::{C_SkFlattenable_getTypeName, C_SkFlattenable_serialize, SkFlattenable};
+use crate::{Data, DeserialProcs, SerialProcs};
+use skia_bindings::{
+    C_SkFlattenable_getTypeName, C_SkFlattenable_serialize, RustDeserialProcs, SkFlattenable,
+};
 use std::ffi::CStr;
+use std::ptr;
 
 // TODO: getFactory()?
 // TODO: NameToFactory()?
 // TODO: FactoryToName()?
 // TODO: Register()?
 // TODO: getFlattenableType()?
-// TODO: serialize() with SkSerialProcs?
 
 pub trait Flattenable: Sized {
     fn type_name(&self) -> &CStr;
     fn serialize(&self) -> Data;
     fn deserialize(data: &[u8]) -> Option<Self>;
+
+    /// The default implementation ignores the procs.
+    fn serialize_with_procs(&self, _procs: &mut SerialProcs) -> Data {
+        self.serialize()
+    }
+
+    /// The default implementation ignores the procs.
+    fn deserialize_with_procs(data: &[u8], _procs: &mut DeserialProcs) -> Option<Self> {
+        Self::deserialize(data)
+    }
 }
 
 // TODO: find a way to hide these trait's functions from other crates.
 pub trait NativeFlattenable {
     fn native_flattenable(&self) -> &SkFlattenable;
     fn native_deserialize(data: &[u8]) -> *mut Self;
+
+    /// The default implementation ignores the procs.
+    fn native_deserialize_with_procs(data: &[u8], _procs: *const RustDeserialProcs) -> *mut Self {
+        Self::native_deserialize(data)
+    }
 }
 
 impl<N> Flattenable for RCHandle<N>
@@ -35,11 +52,30 @@ where
     }
 
     fn serialize(&self) -> Data {
-        Data::from_ptr(unsafe { C_SkFlattenable_serialize(self.native().native_flattenable()) })
-            .unwrap()
+        Data::from_ptr(unsafe {
+            C_SkFlattenable_serialize(self.native().native_flattenable(), ptr::null())
+        })
+        .unwrap()
     }
 
     fn deserialize(data: &[u8]) -> Option<Self> {
         RCHandle::from_ptr(N::native_deserialize(data))
     }
+
+    fn serialize_with_procs(&self, procs: &mut SerialProcs) -> Data {
+        let native_procs = procs.native();
+        let data = Data::from_ptr(unsafe {
+            C_SkFlattenable_serialize(self.native().native_flattenable(), &native_procs)
+        });
+        procs.resume_panic();
+        data.unwrap()
+    }
+
+    fn deserialize_with_procs(data: &[u8], procs: &mut DeserialProcs) -> Option<Self> {
+        let native_procs = procs.native();
+        let deserialized =
+            RCHandle::from_ptr(N::native_deserialize_with_procs(data, &native_procs));
+        procs.resume_panic();
+        deserialized
+    }
 }
@@ -1,12 +1,14 @@
 use crate::prelude::*;
+use crate::sfnt::color::ColorGlyphs;
 use crate::{
-    scalar, FontHinting, FontMetrics, GlyphId, Paint, Path, Point, Rect, TextEncoding, Typeface,
-    Unichar,
+    scalar, AlphaType, Bitmap, Color, FontHinting, FontMetrics, GlyphId, IPoint, IRect, ImageInfo,
+    Paint, Path, PixelGeometry, Point, Rect, RoundOut, Surface, SurfaceProps, TextBlob,
+    TextEncoding, Typeface, Unichar, Vector,
 };
 use skia_bindings::{
     C_SkFont_ConstructFromTypeface, C_SkFont_ConstructFromTypefaceWithSize,
-    C_SkFont_ConstructFromTypefaceWithSizeScaleAndSkew, C_SkFont_Equals, C_SkFont_destruct,
-    C_SkFont_makeWithSize, C_SkFont_setTypeface, SkFont, SkFont_Edging,
+    C_SkFont_ConstructFromTypefaceWithSizeScaleAndSkew, C_SkFont_CopyConstruct, C_SkFont_Equals,
+    C_SkFont_destruct, C_SkFont_makeWithSize, C_SkFont_setTypeface, SkFont, SkFont_Edging,
 };
 use std::{mem, ptr};
 
@@ -32,6 +34,16 @@ impl NativeDrop for SkFont {
     }
 }
 
+impl NativeClone for SkFont {
+    fn clone(&self) -> Self {
+        unsafe {
+            let mut font = mem::zeroed();
+            C_SkFont_CopyConstruct(&mut font, self);
+            font
+        }
+    }
+}
+
 impl NativePartialEq for SkFont {
     fn eq(&self, rhs: &Self) -> bool {
         unsafe { C_SkFont_Equals(self, rhs) }
@@ -335,6 +347,11 @@ impl Handle<SkFont> {
         self.get_widths_bounds(glyphs, widths, bounds, paint)
     }
 
+    /// Returns the advances and bounds of the glyphs, scaled to the font's size.
+    ///
+    /// The bitmaps of color glyphs are scaled from the strike that matches the size best, so
+    /// their bounds are the bounds of the scaled bitmap and may exceed the font's ascent and
+    /// descent.
     pub fn get_widths_bounds(
         &self,
         glyphs: &[GlyphId],
@@ -439,4 +456,223 @@ impl Handle<SkFont> {
     pub fn spacing(&self) -> scalar {
         unsafe { self.native().getMetrics(ptr::null_mut()) }
     }
+
+    // rust specific, rasterizes the glyph the same way a raster canvas draws it.
+
+    /// Rasterizes a glyph with the font's hinting, edging and embolden settings applied.
+    ///
+    /// The glyph's origin is placed at `subpixel_offset`, which takes effect only if the font
+    /// is set to subpixel positioning. `surface_props` provide the pixel geometry that is
+    /// used for `Edging::SubpixelAntiAlias`, if it's missing or its pixel geometry is unknown,
+    /// the glyph is rasterized to an A8 mask instead.
+    ///
+    /// Color glyphs, like emoji, are rasterized in their own colors to an ARGB bitmap.
+    ///
+    /// Returns `None` if the glyph does not cover any pixels, like the glyph of a space.
+    pub fn rasterize_glyph(
+        &self,
+        glyph: GlyphId,
+        subpixel_offset: impl Into<Vector>,
+        surface_props: Option<&SurfaceProps>,
+    ) -> Option<RasterizedGlyph> {
+        let subpixel_offset = subpixel_offset.into();
+
+        if self.is_color_glyph(glyph) {
+            return self.rasterize(glyph, subpixel_offset, GlyphMaskFormat::ARGB, None);
+        }
+
+        let pixel_geometry = surface_props
+            .map(|props| props.pixel_geometry())
+            .unwrap_or_default();
+        let format = match (self.edging(), pixel_geometry) {
+            (Edging::SubpixelAntiAlias, PixelGeometry::Unknown) => GlyphMaskFormat::A8,
+            (Edging::SubpixelAntiAlias, _) => GlyphMaskFormat::LCD,
+            _ => GlyphMaskFormat::A8,
+        };
+        self.rasterize(glyph, subpixel_offset, format, surface_props)
+    }
+
+    /// Returns `true` if the glyph is drawn in its own colors and ignores the paint's color,
+    /// like the color bitmap glyphs of `CBDT` or `sbix` fonts and the layered glyphs of `COLR`
+    /// fonts.
+    ///
+    /// The color tables of a typeface are parsed on the first call only.
+    pub fn is_color_glyph(&self, glyph: GlyphId) -> bool {
+        let typeface = self.typeface_or_default();
+        typeface.has_color_glyphs() && ColorGlyphs::cached(&typeface).contains(glyph)
+    }
+
+    fn rasterize(
+        &self,
+        glyph: GlyphId,
+        subpixel_offset: Vector,
+        format: GlyphMaskFormat,
+        surface_props: Option<&SurfaceProps>,
+    ) -> Option<RasterizedGlyph> {
+        let mut bounds = [Rect::default()];
+        self.get_bounds(&[glyph], &mut bounds, None);
+        if bounds[0].is_empty() {
+            return None;
+        }
+        // hinting and the LCD filter may extend the mask beyond the unhinted bounds.
+        let bounds: IRect = bounds[0]
+            .with_offset(subpixel_offset)
+            .round_out()
+            .with_outset((1, 1));
+
+        let (info, background, foreground) = match format {
+            GlyphMaskFormat::A8 => (
+                ImageInfo::new_a8(bounds.size()),
+                Color::TRANSPARENT,
+                Color::BLACK,
+            ),
+            GlyphMaskFormat::LCD => (
+                ImageInfo::new_n32(bounds.size(), AlphaType::Opaque, None),
+                Color::BLACK,
+                Color::WHITE,
+            ),
+            GlyphMaskFormat::ARGB => (
+                ImageInfo::new_n32_premul(bounds.size(), None),
+                Color::TRANSPARENT,
+                Color::BLACK,
+            ),
+        };
+
+        let mut surface = Surface::new_raster(&info, None, surface_props)?;
+        let blob = TextBlob::from_text(&glyph.to_ne_bytes(), TextEncoding::GlyphId, self)?;
+        let mut paint = Paint::default();
+        paint.set_color(foreground);
+        let canvas = surface.canvas();
+        canvas.clear(background);
+        canvas.draw_text_blob(
+            &blob,
+            (
+                subpixel_offset.x - bounds.left as scalar,
+                subpixel_offset.y - bounds.top as scalar,
+            ),
+            &paint,
+        );
+
+        let mut bitmap = Bitmap::new();
+        if !bitmap.try_alloc_pixels_info(&info, None)
+            || !canvas.read_pixels_to_bitmap(&mut bitmap, (0, 0))
+        {
+            return None;
+        }
+        bitmap.set_immutable();
+
+        Some(RasterizedGlyph {
+            bitmap,
+            bearing: bounds.left_top(),
+            format,
+        })
+    }
+}
+
+/// The pixel format of a rasterized glyph.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum GlyphMaskFormat {
+    /// 8 bits of coverage per pixel, stored in an `ColorType::Alpha8` bitmap.
+    A8,
+    /// Coverage for each of the red, green and blue subpixels, stored in the color channels of
+    /// an opaque N32 bitmap.
+    LCD,
+    /// The colors of a color glyph, stored in a premultiplied N32 bitmap.
+    ARGB,
+}
+
+/// A glyph rasterized by `Font::rasterize_glyph()`.
+pub struct RasterizedGlyph {
+    pub bitmap: Bitmap,
+    /// The position of the bitmap's top left pixel relative to the glyph's origin without the
+    /// subpixel offset.
+    pub bearing: IPoint,
+    pub format: GlyphMaskFormat,
+}
+
+#[test]
+fn rasterize_glyph_to_a8_and_lcd_masks() {
+    use crate::{ColorType, SurfacePropsFlags};
+
+    let mut font = Font::from_typeface(&Typeface::default(), 24.0);
+    let glyph = font.unichar_to_glyph('A' as Unichar);
+
+    let a8 = font.rasterize_glyph(glyph, (0.0, 0.0), None).unwrap();
+    assert_eq!(a8.format, GlyphMaskFormat::A8);
+    assert_eq!(a8.bitmap.color_type(), ColorType::Alpha8);
+    assert!(a8.bearing.y < 0);
+    let covered = (0..a8.bitmap.height())
+        .flat_map(|y| (0..a8.bitmap.width()).map(move |x| (x, y)))
+        .any(|p| a8.bitmap.get_alpha_f(p) > 0.0);
+    assert!(covered);
+
+    font.set_edging(Edging::SubpixelAntiAlias);
+    let props = SurfaceProps::new(SurfacePropsFlags::default(), PixelGeometry::RGBH);
+    let lcd = font
+        .rasterize_glyph(glyph, (0.5, 0.0), Some(&props))
+        .unwrap();
+    assert_eq!(lcd.format, GlyphMaskFormat::LCD);
+    assert_eq!(lcd.bitmap.color_type(), ColorType::n32());
+
+    // the coverage differs between the subpixels, and their order follows the pixel geometry.
+    let props = SurfaceProps::new(SurfacePropsFlags::default(), PixelGeometry::BGRH);
+    let bgr = font
+        .rasterize_glyph(glyph, (0.5, 0.0), Some(&props))
+        .unwrap();
+    assert_eq!(bgr.format, GlyphMaskFormat::LCD);
+    assert_eq!(bgr.bitmap.dimensions(), lcd.bitmap.dimensions());
+    let pixels: Vec<(Color, Color)> = (0..lcd.bitmap.height())
+        .flat_map(|y| (0..lcd.bitmap.width()).map(move |x| (x, y)))
+        .map(|p| (lcd.bitmap.get_color(p), bgr.bitmap.get_color(p)))
+        .collect();
+    assert!(pixels
+        .iter()
+        .any(|(rgb, _)| rgb.r() != rgb.g() || rgb.g() != rgb.b()));
+    assert!(pixels.iter().any(|(rgb, _)| rgb.r() != rgb.b()));
+    for (rgb, bgr) in pixels {
+        assert_eq!((rgb.r(), rgb.g(), rgb.b()), (bgr.b(), bgr.g(), bgr.r()));
+    }
+
+    let space = font.unichar_to_glyph(' ' as Unichar);
+    assert!(font.rasterize_glyph(space, (0.0, 0.0), None).is_none());
+}
+
+#[test]
+fn color_glyphs_are_rasterized_in_their_own_colors() {
+    use crate::{ColorType, Data};
+
+    // a CBDT font with a red square for U+1F600, a black square for U+25A0, and a 'A' without
+    // a bitmap. The 16x16 pixel bitmaps are one em wide and tall.
+    let data = Data::new_copy(include_bytes!("../../resources/fonts/color_glyphs.ttf"));
+    let typeface = Typeface::from_data(&data, None).unwrap();
+    assert!(typeface.has_color_glyphs());
+    let font = Font::from_typeface(&typeface, 32.0);
+
+    let red = font.unichar_to_glyph(0x1f600);
+    let black = font.unichar_to_glyph(0x25a0);
+    let a = font.unichar_to_glyph('A' as Unichar);
+    assert_eq!((red, black, a), (1, 2, 3));
+    assert!(font.is_color_glyph(red));
+    assert!(font.is_color_glyph(black));
+    assert!(!font.is_color_glyph(a));
+
+    let mut widths = [0.0; 2];
+    let mut bounds = [Rect::default(); 2];
+    font.get_widths_bounds(&[red, black], Some(&mut widths), Some(&mut bounds), None);
+    for (width, bounds) in widths.iter().zip(bounds.iter()) {
+        assert!((width - 32.0).abs() < 1.0);
+        assert!((bounds.width() - 32.0).abs() < 1.0);
+        assert!((bounds.height() - 32.0).abs() < 1.0);
+    }
+
+    for &(glyph, color) in &[(red, Color::RED), (black, Color::BLACK)] {
+        let rasterized = font.rasterize_glyph(glyph, (0.0, 0.0), None).unwrap();
+        assert_eq!(rasterized.format, GlyphMaskFormat::ARGB);
+        assert_eq!(rasterized.bitmap.color_type(), ColorType::n32());
+        let center = (
+            rasterized.bitmap.width() / 2,
+            rasterized.bitmap.height() / 2,
+        );
+        assert_eq!(rasterized.bitmap.get_color(center), color);
+    }
 }
@@ -1,13 +1,15 @@
 use crate::interop;
-use crate::interop::DynamicMemoryWStream;
+use crate::interop::{DynamicMemoryWStream, RustStream};
 use crate::prelude::*;
-use crate::{FontStyle, Typeface, Unichar};
+use crate::{FontArguments, FontStyle, Typeface, Unichar};
 use skia_bindings::{
-    C_SkFontMgr_RefDefault, C_SkFontMgr_makeFromStream, C_SkFontStyleSet_count,
-    C_SkFontStyleSet_createTypeface, C_SkFontStyleSet_getStyle, C_SkFontStyleSet_matchStyle,
-    SkFontMgr, SkFontStyleSet, SkRefCntBase,
+    C_SkFontMgr_RefDefault, C_SkFontMgr_makeFromRustStream, C_SkFontMgr_makeFromRustStreamArgs,
+    C_SkFontMgr_makeFromStream, C_SkFontStyleSet_count, C_SkFontStyleSet_createTypeface,
+    C_SkFontStyleSet_getStyle, C_SkFontStyleSet_matchStyle, SkFontMgr, SkFontStyleSet,
+    SkRefCntBase,
 };
 use std::ffi::CString;
+use std::io::{Read, Seek};
 use std::mem;
 use std::os::raw::c_char;
 
@@ -182,7 +184,39 @@ impl RCHandle<SkFontMgr> {
         })
     }
 
-    // TODO: makeFromStream(.., ttcIndex).
+    /// Reads the font data from `stream` and creates a typeface from the font at `ttc_index` of a
+    /// font collection, or from the first font if `ttc_index` is `None`.
+    pub fn new_from_stream(
+        &self,
+        stream: impl Read + Seek,
+        ttc_index: impl Into<Option<usize>>,
+    ) -> Option<Typeface> {
+        let stream = RustStream::new(stream).ok()?;
+        Typeface::from_ptr(unsafe {
+            C_SkFontMgr_makeFromRustStream(
+                self.native(),
+                stream.into_native(),
+                ttc_index.into().unwrap_or_default().try_into().unwrap(),
+            )
+        })
+    }
+
+    /// Reads the font data from `stream` and creates a typeface from it. The collection index and
+    /// the variation design position are taken from `arguments`.
+    pub fn new_from_stream_with_arguments(
+        &self,
+        stream: impl Read + Seek,
+        arguments: &FontArguments,
+    ) -> Option<Typeface> {
+        let stream = RustStream::new(stream).ok()?;
+        Typeface::from_ptr(unsafe {
+            C_SkFontMgr_makeFromRustStreamArgs(
+                self.native(),
+                stream.into_native(),
+                arguments.native(),
+            )
+        })
+    }
 }
 
 #[test]
@@ -15,11 +15,14 @@ impl Handle<SkBitmap> {
 }
 
 pub mod encode {
-    // TODO: wrap stream variants.
-
+    use crate::image::CachingHint;
+    use crate::interop::{DynamicMemoryWStream, RustWStream};
     use crate::prelude::*;
-    use crate::{Bitmap, Data, EncodedImageFormat, Pixmap};
-    use skia_bindings::{C_SkEncodeBitmap, C_SkEncodePixmap};
+    use crate::{Bitmap, Data, EncodedImageFormat, Image, Pixmap};
+    use skia_bindings::{
+        C_SkEncodeBitmap, C_SkEncodeImage, C_SkEncodeImage2, C_SkEncodePixmap, SkWStream,
+    };
+    use std::io::{self, Write};
 
     pub fn pixmap(src: &Pixmap, format: EncodedImageFormat, quality: usize) -> Option<Data> {
         Data::from_ptr(unsafe {
@@ -40,4 +43,530 @@ pub mod encode {
             )
         })
     }
+
+    /// Encodes the pixmap and writes the encoded image to `writer`.
+    pub fn pixmap_to_writer(
+        writer: impl Write,
+        src: &Pixmap,
+        format: EncodedImageFormat,
+        quality: usize,
+    ) -> io::Result<()> {
+        encode_to_writer(writer, |dst| unsafe {
+            C_SkEncodeImage(
+                dst,
+                src.native(),
+                format.into_native(),
+                quality.try_into().unwrap(),
+            )
+        })
+    }
+
+    /// Encodes the bitmap and writes the encoded image to `writer`.
+    pub fn bitmap_to_writer(
+        writer: impl Write,
+        src: &Bitmap,
+        format: EncodedImageFormat,
+        quality: usize,
+    ) -> io::Result<()> {
+        encode_to_writer(writer, |dst| unsafe {
+            C_SkEncodeImage2(
+                dst,
+                src.native(),
+                format.into_native(),
+                quality.try_into().unwrap(),
+            )
+        })
+    }
+
+    fn finish(mut stream: RustWStream, encoded: bool) -> io::Result<()> {
+        stream.finish()?;
+        if !encoded {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "failed to encode the image",
+            ));
+        }
+        Ok(())
+    }
+
+    fn encode_to_data(encode: impl FnOnce(&mut SkWStream) -> bool) -> Option<Data> {
+        let mut stream = DynamicMemoryWStream::new();
+        encode(&mut stream.native_mut()._base).if_true_then_some(|| stream.detach_as_data())
+    }
+
+    fn encode_to_writer(
+        writer: impl Write,
+        encode: impl FnOnce(&mut SkWStream) -> bool,
+    ) -> io::Result<()> {
+        let mut stream = RustWStream::new(writer);
+        let encoded = encode(stream.stream_mut());
+        finish(stream, encoded)
+    }
+
+    // Reads the pixels of the image into a raster pixmap, so that it can be encoded with
+    // options.
+    fn encode_image_to_data(
+        image: &Image,
+        encode: impl FnOnce(&mut SkWStream, &Pixmap) -> bool,
+    ) -> Option<Data> {
+        let info = image.image_info();
+        let row_bytes = info.min_row_bytes();
+        let height: usize = info.height().try_into().unwrap();
+        let mut pixels = vec![0u8; height * row_bytes];
+        if !image.read_pixels(info, &mut pixels, row_bytes, (0, 0), CachingHint::Allow) {
+            return None;
+        }
+        let pixmap = Pixmap::new(info, &pixels, row_bytes);
+        encode_to_data(|dst| encode(dst, &pixmap))
+    }
+
+    fn encode_bitmap_to_data(
+        bitmap: &Bitmap,
+        encode: impl FnOnce(&mut SkWStream, &Pixmap) -> bool,
+    ) -> Option<Data> {
+        let pixmap = bitmap.peek_pixels()?;
+        encode_to_data(|dst| encode(dst, &pixmap))
+    }
+
+    pub mod png {
+        use crate::prelude::*;
+        use crate::{Bitmap, Data, DataTable, Image, Pixmap};
+        use skia_bindings::{C_SkPngEncoder_Encode, SkWStream};
+        use std::ffi::CString;
+        use std::io::{self, Write};
+
+        bitflags! {
+            /// The filters the encoder may try on each row, mirrors `SkPngEncoder::FilterFlag`.
+            pub struct FilterFlags: u32 {
+                const NONE = 0x08;
+                const SUB = 0x10;
+                const UP = 0x20;
+                const AVG = 0x40;
+                const PAETH = 0x80;
+                const ALL = Self::NONE.bits
+                    | Self::SUB.bits
+                    | Self::UP.bits
+                    | Self::AVG.bits
+                    | Self::PAETH.bits;
+            }
+        }
+
+        /// A `tEXt` chunk that is written to the encoded image.
+        #[derive(Clone, PartialEq, Eq, Debug)]
+        pub struct Comment {
+            pub keyword: String,
+            pub text: String,
+        }
+
+        /// The options of the PNG encoder.
+        ///
+        /// Skia m75 does not support ICC profile options, the ICC profile is derived from the
+        /// color space of the encoded pixmap.
+        #[derive(Clone, PartialEq, Eq, Debug)]
+        pub struct Options {
+            /// The filters to try, an empty set disables filtering.
+            pub filter_flags: FilterFlags,
+            /// The zlib compression level in the range `0..=9`, `0` disables compression.
+            pub z_lib_level: i32,
+            pub comments: Vec<Comment>,
+        }
+
+        impl Default for Options {
+            fn default() -> Self {
+                Options {
+                    filter_flags: FilterFlags::ALL,
+                    z_lib_level: 6,
+                    comments: Vec::new(),
+                }
+            }
+        }
+
+        impl Options {
+            // panics if a keyword or a text of a comment contains a nul byte.
+            fn comments_table(&self) -> Option<DataTable> {
+                if self.comments.is_empty() {
+                    return None;
+                }
+                let strings: Vec<CString> = self
+                    .comments
+                    .iter()
+                    .flat_map(|c| vec![c.keyword.as_str(), c.text.as_str()])
+                    .map(|s| CString::new(s).unwrap())
+                    .collect();
+                let slices: Vec<&[u8]> = strings.iter().map(|s| s.as_bytes_with_nul()).collect();
+                Some(DataTable::from_slices(&slices))
+            }
+
+            fn encode(&self, dst: &mut SkWStream, src: &Pixmap) -> bool {
+                let mut comments = self.comments_table();
+                unsafe {
+                    C_SkPngEncoder_Encode(
+                        dst,
+                        src.native(),
+                        self.filter_flags.bits() as _,
+                        self.z_lib_level,
+                        comments.as_mut().native_ptr_or_null_mut(),
+                    )
+                }
+            }
+        }
+
+        /// Encodes the pixmap as PNG.
+        pub fn encode(src: &Pixmap, options: &Options) -> Option<Data> {
+            super::encode_to_data(|dst| options.encode(dst, src))
+        }
+
+        /// Encodes the image as PNG.
+        pub fn encode_image(image: &Image, options: &Options) -> Option<Data> {
+            super::encode_image_to_data(image, |dst, src| options.encode(dst, src))
+        }
+
+        /// Encodes the bitmap as PNG.
+        ///
+        /// Returns `None` if the pixels of the bitmap are not accessible.
+        pub fn encode_bitmap(bitmap: &Bitmap, options: &Options) -> Option<Data> {
+            super::encode_bitmap_to_data(bitmap, |dst, src| options.encode(dst, src))
+        }
+
+        /// Encodes the pixmap as PNG and writes the encoded image to `writer`.
+        pub fn encode_to_writer(
+            writer: impl Write,
+            src: &Pixmap,
+            options: &Options,
+        ) -> io::Result<()> {
+            super::encode_to_writer(writer, |dst| options.encode(dst, src))
+        }
+    }
+
+    pub mod jpeg {
+        use crate::prelude::*;
+        use crate::{Bitmap, Data, Image, Pixmap};
+        use skia_bindings::{
+            C_SkJpegEncoder_Encode, SkJpegEncoder_AlphaOption, SkJpegEncoder_Downsample,
+            SkJpegEncoder_Options, SkWStream,
+        };
+        use std::io::{self, Write};
+
+        /// How the chroma channels are downsampled.
+        #[derive(Copy, Clone, PartialEq, Eq, Debug)]
+        #[repr(i32)]
+        pub enum Downsample {
+            /// Reduces the chroma resolution by a factor of 2 in both directions (4:2:0).
+            BothDirections = SkJpegEncoder_Downsample::k420 as _,
+            /// Reduces the chroma resolution by a factor of 2 horizontally (4:2:2).
+            Horizontal = SkJpegEncoder_Downsample::k422 as _,
+            /// Keeps the full chroma resolution (4:4:4).
+            No = SkJpegEncoder_Downsample::k444 as _,
+        }
+
+        impl NativeTransmutable<SkJpegEncoder_Downsample> for Downsample {}
+        #[test]
+        fn test_downsample_layout() {
+            Downsample::test_layout()
+        }
+
+        /// How to handle the alpha channel, JPEG does not support alpha.
+        #[derive(Copy, Clone, PartialEq, Eq, Debug)]
+        #[repr(i32)]
+        pub enum AlphaOption {
+            Ignore = SkJpegEncoder_AlphaOption::kIgnore as _,
+            BlendOnBlack = SkJpegEncoder_AlphaOption::kBlendOnBlack as _,
+        }
+
+        impl NativeTransmutable<SkJpegEncoder_AlphaOption> for AlphaOption {}
+        #[test]
+        fn test_alpha_option_layout() {
+            AlphaOption::test_layout()
+        }
+
+        /// The options of the JPEG encoder.
+        #[derive(Clone, PartialEq, Eq, Debug)]
+        pub struct Options {
+            /// The quality in the range `0..=100`.
+            pub quality: usize,
+            pub downsample: Downsample,
+            pub alpha_option: AlphaOption,
+        }
+
+        impl Default for Options {
+            fn default() -> Self {
+                Options {
+                    quality: 100,
+                    downsample: Downsample::BothDirections,
+                    alpha_option: AlphaOption::Ignore,
+                }
+            }
+        }
+
+        impl Options {
+            fn encode(&self, dst: &mut SkWStream, src: &Pixmap) -> bool {
+                let options = SkJpegEncoder_Options {
+                    fQuality: self.quality.try_into().unwrap(),
+                    fDownsample: self.downsample.into_native(),
+                    fAlphaOption: self.alpha_option.into_native(),
+                };
+                unsafe { C_SkJpegEncoder_Encode(dst, src.native(), &options) }
+            }
+        }
+
+        /// Encodes the pixmap as JPEG.
+        pub fn encode(src: &Pixmap, options: &Options) -> Option<Data> {
+            super::encode_to_data(|dst| options.encode(dst, src))
+        }
+
+        /// Encodes the image as JPEG.
+        pub fn encode_image(image: &Image, options: &Options) -> Option<Data> {
+            super::encode_image_to_data(image, |dst, src| options.encode(dst, src))
+        }
+
+        /// Encodes the bitmap as JPEG.
+        ///
+        /// Returns `None` if the pixels of the bitmap are not accessible.
+        pub fn encode_bitmap(bitmap: &Bitmap, options: &Options) -> Option<Data> {
+            super::encode_bitmap_to_data(bitmap, |dst, src| options.encode(dst, src))
+        }
+
+        /// Encodes the pixmap as JPEG and writes the encoded image to `writer`.
+        pub fn encode_to_writer(
+            writer: impl Write,
+            src: &Pixmap,
+            options: &Options,
+        ) -> io::Result<()> {
+            super::encode_to_writer(writer, |dst| options.encode(dst, src))
+        }
+    }
+
+    pub mod webp {
+        use crate::prelude::*;
+        use crate::{Bitmap, Data, Image, Pixmap};
+        use skia_bindings::{
+            C_SkWebpEncoder_Encode, SkWStream, SkWebpEncoder_Compression, SkWebpEncoder_Options,
+        };
+        use std::io::{self, Write};
+
+        #[derive(Copy, Clone, PartialEq, Eq, Debug)]
+        #[repr(i32)]
+        pub enum Compression {
+            Lossy = SkWebpEncoder_Compression::kLossy as _,
+            Lossless = SkWebpEncoder_Compression::kLossless as _,
+        }
+
+        impl NativeTransmutable<SkWebpEncoder_Compression> for Compression {}
+        #[test]
+        fn test_compression_layout() {
+            Compression::test_layout()
+        }
+
+        /// The options of the WebP encoder.
+        #[derive(Clone, PartialEq, Debug)]
+        pub struct Options {
+            pub compression: Compression,
+            /// For lossy compression, the visual quality in the range `0.0..=100.0`.
+            /// For lossless compression, the effort put into compression in the same range,
+            /// `100.0` produces the smallest files.
+            pub quality: f32,
+        }
+
+        impl Default for Options {
+            fn default() -> Self {
+                Options {
+                    compression: Compression::Lossy,
+                    quality: 100.0,
+                }
+            }
+        }
+
+        impl Options {
+            fn encode(&self, dst: &mut SkWStream, src: &Pixmap) -> bool {
+                let options = SkWebpEncoder_Options {
+                    fCompression: self.compression.into_native(),
+                    fQuality: self.quality,
+                };
+                unsafe { C_SkWebpEncoder_Encode(dst, src.native(), &options) }
+            }
+        }
+
+        /// Encodes the pixmap as WebP.
+        ///
+        /// Returns `None` if Skia was built without WebP support.
+        pub fn encode(src: &Pixmap, options: &Options) -> Option<Data> {
+            super::encode_to_data(|dst| options.encode(dst, src))
+        }
+
+        /// Encodes the image as WebP.
+        pub fn encode_image(image: &Image, options: &Options) -> Option<Data> {
+            super::encode_image_to_data(image, |dst, src| options.encode(dst, src))
+        }
+
+        /// Encodes the bitmap as WebP.
+        ///
+        /// Returns `None` if the pixels of the bitmap are not accessible.
+        pub fn encode_bitmap(bitmap: &Bitmap, options: &Options) -> Option<Data> {
+            super::encode_bitmap_to_data(bitmap, |dst, src| options.encode(dst, src))
+        }
+
+        /// Encodes the pixmap as WebP and writes the encoded image to `writer`.
+        pub fn encode_to_writer(
+            writer: impl Write,
+            src: &Pixmap,
+            options: &Options,
+        ) -> io::Result<()> {
+            super::encode_to_writer(writer, |dst| options.encode(dst, src))
+        }
+    }
+
+    #[test]
+    fn encode_pixmap_to_writer() {
+        use crate::{Color, Surface};
+
+        let mut surface = Surface::new_raster_n32_premul((4, 4)).unwrap();
+        surface.canvas().clear(Color::GREEN);
+        let pixmap = surface.peek_pixels().unwrap();
+
+        let mut bytes = Vec::new();
+        pixmap_to_writer(&mut bytes, &pixmap, EncodedImageFormat::PNG, 100).unwrap();
+        assert_eq!(
+            pixmap
+                .encode(EncodedImageFormat::PNG, 100)
+                .unwrap()
+                .as_bytes(),
+            &bytes[..]
+        );
+    }
+
+    #[test]
+    fn png_options_and_comments() {
+        use crate::{Codec, Color, Surface};
+
+        let mut surface = Surface::new_raster_n32_premul((4, 4)).unwrap();
+        surface.canvas().clear(Color::GREEN);
+        let pixmap = surface.peek_pixels().unwrap();
+
+        let options = png::Options {
+            filter_flags: png::FilterFlags::NONE,
+            z_lib_level: 9,
+            comments: vec![png::Comment {
+                keyword: "Title".into(),
+                text: "Green".into(),
+            }],
+        };
+        let data = png::encode(&pixmap, &options).unwrap();
+        assert!(data
+            .as_bytes()
+            .windows(b"tEXtTitle\0Green".len())
+            .any(|w| w == b"tEXtTitle\0Green"));
+        let codec = Codec::from_data(&data).unwrap();
+        assert_eq!(pixmap.info().dimensions(), codec.dimensions());
+    }
+
+    #[test]
+    fn png_z_lib_level_affects_the_size() {
+        use crate::{Color, Paint, Surface};
+
+        let mut surface = Surface::new_raster_n32_premul((64, 64)).unwrap();
+        let canvas = surface.canvas();
+        canvas.clear(Color::WHITE);
+        canvas.draw_circle((32, 32), 20.0, Paint::default().set_anti_alias(true));
+        let image = surface.image_snapshot();
+
+        let encode = |z_lib_level| {
+            let options = png::Options {
+                z_lib_level,
+                ..png::Options::default()
+            };
+            png::encode_image(&image, &options).unwrap().size()
+        };
+        assert!(encode(9) < encode(0));
+    }
+
+    #[test]
+    fn encode_bitmap_with_options() {
+        use crate::{Codec, Color};
+
+        let mut bitmap = Bitmap::new();
+        bitmap.alloc_n32_pixels((8, 4), None);
+        bitmap.erase_color(Color::GREEN);
+        let data = jpeg::encode_bitmap(&bitmap, &jpeg::Options::default()).unwrap();
+        let codec = Codec::from_data(&data).unwrap();
+        assert_eq!(EncodedImageFormat::JPEG, codec.encoded_format());
+        assert_eq!(bitmap.info().dimensions(), codec.dimensions());
+
+        assert!(png::encode_bitmap(&Bitmap::new(), &png::Options::default()).is_none());
+    }
+
+    #[test]
+    fn jpeg_quality_affects_the_size() {
+        use crate::{Color, Paint, Surface};
+
+        let mut surface = Surface::new_raster_n32_premul((64, 64)).unwrap();
+        let canvas = surface.canvas();
+        canvas.clear(Color::WHITE);
+        canvas.draw_circle((32, 32), 20.0, Paint::default().set_anti_alias(true));
+        let pixmap = surface.peek_pixels().unwrap();
+
+        let encode = |quality| {
+            let options = jpeg::Options {
+                quality,
+                downsample: jpeg::Downsample::No,
+                ..jpeg::Options::default()
+            };
+            jpeg::encode(&pixmap, &options).unwrap().size()
+        };
+        assert!(encode(10) < encode(100));
+    }
+
+    #[cfg(feature = "webp")]
+    fn webp_test_image() -> crate::Image {
+        use crate::{Color, Paint, Surface};
+
+        let mut surface = Surface::new_raster_n32_premul((32, 16)).unwrap();
+        let canvas = surface.canvas();
+        canvas.clear(Color::BLUE);
+        canvas.draw_rect(
+            crate::Rect::new(0.0, 0.0, 16.0, 16.0),
+            Paint::default().set_color(Color::RED),
+        );
+        surface.image_snapshot()
+    }
+
+    #[test]
+    #[cfg(feature = "webp")]
+    fn webp_round_trip() {
+        use crate::Image;
+
+        let image = webp_test_image();
+        let data = image.encode_to_data(EncodedImageFormat::WEBP).unwrap();
+        assert_eq!(&b"RIFF"[..], &data.as_bytes()[..4]);
+        assert_eq!(&b"WEBP"[..], &data.as_bytes()[8..12]);
+
+        let decoded = Image::from_encoded(&data, None).unwrap();
+        assert_eq!(image.dimensions(), decoded.dimensions());
+
+        let codec = crate::Codec::from_data(&data).unwrap();
+        assert_eq!(EncodedImageFormat::WEBP, codec.encoded_format());
+    }
+
+    #[test]
+    #[cfg(feature = "webp")]
+    fn webp_lossless_round_trip() {
+        use crate::{Codec, Color, ImageInfo};
+
+        let image = webp_test_image();
+        let options = webp::Options {
+            compression: webp::Compression::Lossless,
+            ..webp::Options::default()
+        };
+        let data = webp::encode_image(&image, &options).unwrap();
+
+        let mut codec = Codec::from_data(&data).unwrap();
+        let info = ImageInfo::new_n32_premul(codec.dimensions(), None);
+        let pixels = vec![0u8; info.compute_min_byte_size()];
+        let decoded = Pixmap::new(&info, &pixels, info.min_row_bytes());
+        assert_eq!(
+            crate::codec::Result::Success,
+            codec.get_pixels(&decoded, None)
+        );
+        assert_eq!(Color::RED, decoded.get_color((8, 8)));
+        assert_eq!(Color::BLUE, decoded.get_color((24, 8)));
+    }
 }
@@ -4,10 +4,10 @@ use crate::{
 };
 use skia_bindings::{
     C_SkImageFilter_Deserialize, C_SkImageFilter_MakeMatrixFilter,
-    C_SkImageFilter_computeFastBounds, C_SkImageFilter_makeWithLocalMatrix, SkColorFilter,
-    SkColorSpace, SkFlattenable, SkImageFilter, SkImageFilterCache, SkImageFilter_Context,
-    SkImageFilter_CropRect, SkImageFilter_MapDirection, SkImageFilter_OutputProperties,
-    SkImageFilter_TileUsage, SkRefCntBase,
+    C_SkImageFilter_computeFastBounds, C_SkImageFilter_makeWithLocalMatrix, RustDeserialProcs,
+    SkColorFilter, SkColorSpace, SkFlattenable, SkImageFilter, SkImageFilterCache,
+    SkImageFilter_Context, SkImageFilter_CropRect, SkImageFilter_MapDirection,
+    SkImageFilter_OutputProperties, SkImageFilter_TileUsage, SkRefCntBase,
 };
 use std::ptr;
 
@@ -178,7 +178,11 @@ impl NativeFlattenable for SkImageFilter {
     }
 
     fn native_deserialize(data: &[u8]) -> *mut Self {
-        unsafe { C_SkImageFilter_Deserialize(data.as_ptr() as _, data.len()) }
+        Self::native_deserialize_with_procs(data, ptr::null())
+    }
+
+    fn native_deserialize_with_procs(data: &[u8], procs: *const RustDeserialProcs) -> *mut Self {
+        unsafe { C_SkImageFilter_Deserialize(data.as_ptr() as _, data.len(), procs) }
     }
 }
 
@@ -2,9 +2,10 @@ use crate::prelude::*;
 use crate::{scalar, BlurStyle, CoverageMode, Matrix, NativeFlattenable};
 use skia_bindings::{
     C_SkMaskFilter_Combine, C_SkMaskFilter_Compose, C_SkMaskFilter_Deserialize,
-    C_SkMaskFilter_MakeBlur, C_SkMaskFilter_makeWithMatrix, SkFlattenable, SkMaskFilter,
-    SkRefCntBase,
+    C_SkMaskFilter_MakeBlur, C_SkMaskFilter_makeWithMatrix, RustDeserialProcs, SkFlattenable,
+    SkMaskFilter, SkRefCntBase,
 };
+use std::ptr;
 
 pub type MaskFilter = RCHandle<SkMaskFilter>;
 
@@ -22,7 +23,11 @@ impl NativeFlattenable for SkMaskFilter {
     }
 
     fn native_deserialize(data: &[u8]) -> *mut Self {
-        unsafe { C_SkMaskFilter_Deserialize(data.as_ptr() as _, data.len()) }
+        Self::native_deserialize_with_procs(data, ptr::null())
+    }
+
+    fn native_deserialize_with_procs(data: &[u8], procs: *const RustDeserialProcs) -> *mut Self {
+        unsafe { C_SkMaskFilter_Deserialize(data.as_ptr() as _, data.len(), procs) }
     }
 }
 
@@ -2,12 +2,12 @@ use crate::prelude::*;
 use crate::{scalar, Matrix, NativeFlattenable, Path, Point, Rect, StrokeRec, Vector};
 use skia_bindings::{
     C_SkPathEffect_Deserialize, C_SkPathEffect_MakeCompose, C_SkPathEffect_MakeSum,
-    C_SkPathEffect_PointData_Construct, C_SkPathEffect_PointData_deletePoints, SkFlattenable,
-    SkPathEffect, SkPathEffect_DashInfo, SkPathEffect_DashType, SkPathEffect_PointData,
-    SkRefCntBase,
+    C_SkPathEffect_PointData_Construct, C_SkPathEffect_PointData_deletePoints, RustDeserialProcs,
+    SkFlattenable, SkPathEffect, SkPathEffect_DashInfo, SkPathEffect_DashType,
+    SkPathEffect_PointData, SkRefCntBase,
 };
 use std::os::raw;
-use std::{mem, slice};
+use std::{mem, ptr, slice};
 
 #[repr(C)]
 pub struct PointData {
@@ -91,7 +91,11 @@ impl NativeFlattenable for SkPathEffect {
     }
 
     fn native_deserialize(data: &[u8]) -> *mut Self {
-        unsafe { C_SkPathEffect_Deserialize(data.as_ptr() as _, data.len()) }
+        Self::native_deserialize_with_procs(data, ptr::null())
+    }
+
+    fn native_deserialize_with_procs(data: &[u8], procs: *const RustDeserialProcs) -> *mut Self {
+        unsafe { C_SkPathEffect_Deserialize(data.as_ptr() as _, data.len(), procs) }
     }
 }
 
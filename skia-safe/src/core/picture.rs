@@ -1,11 +1,20 @@
+use crate::interop::RustStream;
 use crate::prelude::*;
-use crate::{Canvas, Data, Rect};
+use crate::{Canvas, Data, DeserialProcs, Rect, SerialProcs};
 use crate::{Matrix, Shader, TileMode};
 use skia_bindings::{
-    C_SkPicture_MakeFromData, C_SkPicture_MakeFromData2, C_SkPicture_MakePlaceholder,
+    C_RangePlaybackCanvas_new, C_RangePlaybackCanvas_setDraw, C_SkPicture_MakeFromData,
+    C_SkPicture_MakeFromData2, C_SkPicture_MakeFromStream, C_SkPicture_MakePlaceholder,
     C_SkPicture_approximateBytesUsed, C_SkPicture_approximateOpCount, C_SkPicture_cullRect,
-    C_SkPicture_makeShader, C_SkPicture_playback, C_SkPicture_serialize, SkPicture, SkRefCntBase,
+    C_SkPicture_makeShader, C_SkPicture_playback, C_SkPicture_playbackWithAbort,
+    C_SkPicture_serialize, SkCanvas, SkPicture, SkRefCntBase,
 };
+use std::any::Any;
+use std::ffi::c_void;
+use std::io::{Read, Seek};
+use std::ops::Range;
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
 
 pub type Picture = RCHandle<SkPicture>;
 
@@ -18,24 +27,117 @@ impl NativeRefCountedBase for SkPicture {
 }
 
 impl RCHandle<SkPicture> {
-    // TODO: wrap MakeFromStream
-
-    // TODO: may support SkSerialProces in MakeFromData?
-
     pub fn from_data(data: &Data) -> Option<Picture> {
-        Picture::from_ptr(unsafe { C_SkPicture_MakeFromData(data.native()) })
+        Picture::from_ptr(unsafe { C_SkPicture_MakeFromData(data.native(), ptr::null()) })
+    }
+
+    pub fn from_data_with_procs(data: &Data, procs: &mut DeserialProcs) -> Option<Picture> {
+        let native_procs = procs.native();
+        let picture =
+            Picture::from_ptr(unsafe { C_SkPicture_MakeFromData(data.native(), &native_procs) });
+        procs.resume_panic();
+        picture
     }
 
     pub fn from_bytes(bytes: &[u8]) -> Option<Picture> {
-        Picture::from_ptr(unsafe { C_SkPicture_MakeFromData2(bytes.as_ptr() as _, bytes.len()) })
+        Picture::from_ptr(unsafe {
+            C_SkPicture_MakeFromData2(bytes.as_ptr() as _, bytes.len(), ptr::null())
+        })
     }
 
-    // TODO: AbortCallback and the function that use it.
+    pub fn from_bytes_with_procs(bytes: &[u8], procs: &mut DeserialProcs) -> Option<Picture> {
+        let native_procs = procs.native();
+        let picture = Picture::from_ptr(unsafe {
+            C_SkPicture_MakeFromData2(bytes.as_ptr() as _, bytes.len(), &native_procs)
+        });
+        procs.resume_panic();
+        picture
+    }
+
+    /// Reads a serialized picture from the current position of `stream`.
+    pub fn from_stream(stream: &mut (impl Read + Seek)) -> Option<Picture> {
+        let mut stream = RustStream::new(stream).ok()?;
+        Picture::from_ptr(unsafe { C_SkPicture_MakeFromStream(stream.stream_mut(), ptr::null()) })
+    }
 
     pub fn playback(&self, mut canvas: impl AsMut<Canvas>) {
         unsafe { C_SkPicture_playback(self.native(), canvas.as_mut().native_mut()) }
     }
 
+    /// Plays back the picture and invokes `abort` with the index of each op before it is
+    /// drawn. The playback stops as soon as `abort` returns `true`.
+    ///
+    /// Pictures that consist of a single op may not invoke `abort` at all. After the playback,
+    /// the canvas is restored to the save count it had before. If `abort` panics, the playback
+    /// stops and the panic is resumed after the canvas is restored.
+    ///
+    /// The index counts the ops that are played back. If the picture was recorded with a
+    /// bounding box hierarchy and the canvas' clip does not contain the picture's cull rect,
+    /// only the ops that intersect the clip are played back, and the indices do not match the
+    /// indices of the recorded ops anymore.
+    pub fn playback_with_abort<F: FnMut(usize) -> bool>(
+        &self,
+        mut canvas: impl AsMut<Canvas>,
+        abort: F,
+    ) {
+        let canvas = canvas.as_mut();
+        let save_count = canvas.save_count();
+        let mut state = AbortState {
+            index: 0,
+            abort,
+            panic: None,
+        };
+        unsafe {
+            C_SkPicture_playbackWithAbort(
+                self.native(),
+                canvas.native_mut(),
+                &mut state as *mut _ as _,
+                Some(abort_trampoline::<F>),
+            )
+        }
+        canvas.restore_to_count(save_count);
+        if let Some(panic) = state.panic {
+            panic::resume_unwind(panic)
+        }
+    }
+
+    /// Plays back the ops of the picture in `range`.
+    ///
+    /// The ops before the range are played back too, so that their matrix and clip changes
+    /// apply, but they do not draw. Drawables and annotations can not be filtered and are
+    /// drawn even if they are outside of the range.
+    ///
+    /// The range refers to the indices passed to the `abort` function of
+    /// `playback_with_abort()`, see there for pictures with a bounding box hierarchy.
+    pub fn playback_range(&self, canvas: impl AsMut<Canvas>, range: Range<usize>) {
+        self.playback_range_with_abort(canvas, range, |_| false)
+    }
+
+    /// Plays back the ops of the picture in `range` and invokes `abort` with the index of each
+    /// op before it is played back. The playback stops as soon as `abort` returns `true`.
+    pub fn playback_range_with_abort(
+        &self,
+        mut canvas: impl AsMut<Canvas>,
+        range: Range<usize>,
+        mut abort: impl FnMut(usize) -> bool,
+    ) {
+        let canvas = canvas.as_mut();
+        // pictures that consist of a single op do not invoke the abort callback.
+        let draw_first = range.start == 0 && range.start < range.end;
+        let mut range_canvas = Canvas::own_from_native_ptr(unsafe {
+            C_RangePlaybackCanvas_new(canvas.native_mut(), draw_first)
+        })
+        .unwrap();
+        let range_canvas_ptr = range_canvas.native_mut() as *mut SkCanvas;
+        self.playback_with_abort(&mut range_canvas, |index| {
+            if index >= range.end || abort(index) {
+                return true;
+            }
+            unsafe { C_RangePlaybackCanvas_setDraw(range_canvas_ptr, index >= range.start) }
+            false
+        });
+    }
+
     pub fn cull_rect(&self) -> Rect {
         Rect::from_native(unsafe { C_SkPicture_cullRect(self.native()) })
     }
@@ -44,10 +146,15 @@ impl RCHandle<SkPicture> {
         unsafe { self.native().uniqueID() }
     }
 
-    // TODO: support SkSerialProcs in serialize()?
-
     pub fn serialize(&self) -> Data {
-        Data::from_ptr(unsafe { C_SkPicture_serialize(self.native()) }).unwrap()
+        Data::from_ptr(unsafe { C_SkPicture_serialize(self.native(), ptr::null()) }).unwrap()
+    }
+
+    pub fn serialize_with_procs(&self, procs: &mut SerialProcs) -> Data {
+        let native_procs = procs.native();
+        let data = Data::from_ptr(unsafe { C_SkPicture_serialize(self.native(), &native_procs) });
+        procs.resume_panic();
+        data.unwrap()
     }
 
     pub fn new_placeholder(cull: impl AsRef<Rect>) -> Picture {
@@ -94,3 +201,284 @@ impl RCHandle<SkPicture> {
         .unwrap()
     }
 }
+
+struct AbortState<F> {
+    index: usize,
+    abort: F,
+    panic: Option<Box<dyn Any + Send>>,
+}
+
+unsafe extern "C" fn abort_trampoline<F: FnMut(usize) -> bool>(data: *mut c_void) -> bool {
+    let state = &mut *(data as *mut AbortState<F>);
+    let (index, abort) = (state.index, &mut state.abort);
+    state.index += 1;
+    match panic::catch_unwind(AssertUnwindSafe(|| abort(index))) {
+        Ok(aborted) => aborted,
+        Err(panic) => {
+            state.panic = Some(panic);
+            true
+        }
+    }
+}
+
+#[test]
+fn serialize_and_deserialize_from_stream() {
+    use crate::{Paint, PictureRecorder};
+    use std::io::Cursor;
+
+    let mut recorder = PictureRecorder::new();
+    let canvas = recorder.begin_recording(Rect::new(0.0, 0.0, 10.0, 10.0), None, None);
+    canvas.draw_circle((5, 5), 4.0, &Paint::default());
+    let picture = recorder.finish_recording_as_picture(None).unwrap();
+
+    let mut bytes = b"prefix".to_vec();
+    bytes.extend_from_slice(picture.serialize().as_bytes());
+    let mut stream = Cursor::new(bytes);
+    stream.set_position(6);
+    let deserialized = Picture::from_stream(&mut stream).unwrap();
+    assert_eq!(picture.cull_rect(), deserialized.cull_rect());
+}
+
+#[cfg(test)]
+fn painted_thirds(draw: impl FnOnce(&mut Canvas)) -> [bool; 3] {
+    use crate::{Color, ImageInfo, Surface};
+
+    let mut surface = Surface::new_raster_n32_premul((30, 10)).unwrap();
+    surface.canvas().clear(Color::WHITE);
+    draw(surface.canvas());
+    let info = ImageInfo::new_n32_premul((30, 10), None);
+    let mut pixels = vec![0u8; 30 * 10 * 4];
+    assert!(surface.read_pixels(&info, &mut pixels, 30 * 4, (0, 0)));
+    let painted = |x: usize| pixels[x * 4..][..4] != [0xff, 0xff, 0xff, 0xff];
+    [painted(5), painted(15), painted(25)]
+}
+
+#[cfg(test)]
+fn three_rects() -> Picture {
+    use crate::{Paint, PictureRecorder};
+
+    let mut recorder = PictureRecorder::new();
+    let canvas = recorder.begin_recording(Rect::from_wh(30.0, 10.0), None, None);
+    let paint = Paint::default();
+    canvas.draw_rect(Rect::from_wh(10.0, 10.0), &paint);
+    canvas.translate((10.0, 0.0));
+    canvas.draw_rect(Rect::from_wh(10.0, 10.0), &paint);
+    canvas.draw_rect(Rect::new(10.0, 0.0, 20.0, 10.0), &paint);
+    recorder.finish_recording_as_picture(None).unwrap()
+}
+
+#[test]
+fn playback_with_abort_stops_before_the_aborted_op() {
+    let picture = three_rects();
+    let mut indices = Vec::new();
+    let painted = painted_thirds(|canvas| {
+        picture.playback_with_abort(canvas, |index| {
+            indices.push(index);
+            index == 2
+        })
+    });
+    assert_eq!(indices, [0, 1, 2]);
+    assert_eq!(painted, [true, false, false]);
+}
+
+#[test]
+fn playback_range_applies_the_matrix_of_skipped_ops() {
+    let picture = three_rects();
+    let painted = painted_thirds(|canvas| picture.playback_range(canvas, 2..3));
+    assert_eq!(painted, [false, true, false]);
+    let painted = painted_thirds(|canvas| picture.playback_range(canvas, 0..4));
+    assert_eq!(painted, [true, true, true]);
+}
+
+#[test]
+fn playback_with_abort_resumes_panics() {
+    let picture = three_rects();
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        painted_thirds(|canvas| {
+            picture.playback_with_abort(canvas, |index| {
+                if index == 1 {
+                    panic!("abort")
+                }
+                false
+            });
+        })
+    }));
+    assert_eq!(*result.unwrap_err().downcast::<&str>().unwrap(), "abort");
+}
+
+#[test]
+fn playback_with_abort_indices_skip_culled_ops_of_pictures_with_a_bbh() {
+    use crate::{BBHFactory, ClipOp, Paint, PictureRecorder};
+
+    let mut recorder = PictureRecorder::new();
+    let mut rtree = BBHFactory::new_rtree();
+    let canvas = recorder.begin_recording(Rect::from_wh(30.0, 10.0), Some(&mut rtree), None);
+    let paint = Paint::default();
+    canvas.draw_rect(Rect::from_wh(10.0, 10.0), &paint);
+    canvas.draw_rect(Rect::new(10.0, 0.0, 20.0, 10.0), &paint);
+    canvas.draw_rect(Rect::new(20.0, 0.0, 30.0, 10.0), &paint);
+    let picture = recorder.finish_recording_as_picture(None).unwrap();
+
+    let played = |clip: Rect| {
+        let mut indices = Vec::new();
+        painted_thirds(|canvas| {
+            canvas.clip_rect(clip, ClipOp::Intersect, false);
+            picture.playback_with_abort(canvas, |index| {
+                indices.push(index);
+                false
+            })
+        });
+        indices
+    };
+    assert_eq!(played(Rect::from_wh(30.0, 10.0)), [0, 1, 2]);
+    // only the last rect intersects the clip, its index is 0.
+    assert_eq!(played(Rect::new(22.0, 2.0, 28.0, 8.0)), [0]);
+}
+
+#[test]
+fn serialize_and_deserialize_images_with_procs() {
+    use crate::utils::DrawOp;
+    use crate::{PictureRecorder, Surface};
+
+    let image = Surface::new_raster_n32_premul((4, 4))
+        .unwrap()
+        .image_snapshot();
+    let mut recorder = PictureRecorder::new();
+    let canvas = recorder.begin_recording(Rect::from_wh(10.0, 10.0), None, None);
+    canvas.draw_image(&image, (1, 1), None);
+    let picture = recorder.finish_recording_as_picture(None).unwrap();
+
+    let mut serialized_ids = Vec::new();
+    let mut serial_procs = SerialProcs::default();
+    serial_procs.set_image_proc(|image| {
+        serialized_ids.push(image.unique_id());
+        Some(Data::new_copy(b"image"))
+    });
+    let data = picture.serialize_with_procs(&mut serial_procs);
+    drop(serial_procs);
+    assert_eq!(serialized_ids, [image.unique_id()]);
+
+    let mut deserial_procs = DeserialProcs::default();
+    deserial_procs.set_image_proc(|bytes| {
+        assert_eq!(bytes, b"image");
+        Some(image.clone())
+    });
+    let deserialized = Picture::from_data_with_procs(&data, &mut deserial_procs).unwrap();
+    let images: Vec<u32> = deserialized
+        .ops()
+        .into_iter()
+        .filter_map(|op| match op.op {
+            DrawOp::DrawImage { image, .. } => Some(image.unique_id()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(images, [image.unique_id()]);
+}
+
+#[test]
+fn serialize_and_deserialize_typefaces_with_procs() {
+    use crate::typeface::SerializeBehavior;
+    use crate::utils::DrawOp;
+    use crate::{Font, Paint, PictureRecorder, TextBlob, Typeface};
+    use std::collections::hash_map::DefaultHasher;
+    use std::collections::HashMap;
+    use std::hash::{Hash, Hasher};
+
+    let typeface = Typeface::default();
+    let font = Font::from_typeface(&typeface, 12.0);
+    let blob = TextBlob::from_str("Hello", &font).unwrap();
+    let mut recorder = PictureRecorder::new();
+    let canvas = recorder.begin_recording(Rect::from_wh(100.0, 20.0), None, None);
+    canvas.draw_text_blob(&blob, (0, 12), &Paint::default());
+    let picture = recorder.finish_recording_as_picture(None).unwrap();
+
+    // the typefaces are serialized as the hash of their content.
+    let mut typefaces = HashMap::new();
+    let mut serial_procs = SerialProcs::default();
+    serial_procs.set_typeface_proc(|typeface| {
+        let mut hasher = DefaultHasher::new();
+        typeface
+            .serialize(SerializeBehavior::DoIncludeData)
+            .as_bytes()
+            .hash(&mut hasher);
+        let hash = hasher.finish().to_le_bytes();
+        typefaces.insert(hash, typeface.clone());
+        Some(Data::new_copy(&hash))
+    });
+    let data = picture.serialize_with_procs(&mut serial_procs);
+    drop(serial_procs);
+    assert_eq!(typefaces.len(), 1);
+
+    let mut deserial_procs = DeserialProcs::default();
+    deserial_procs.set_typeface_proc(|bytes| {
+        let mut hash = [0; 8];
+        hash.copy_from_slice(bytes);
+        typefaces.get(&hash).cloned()
+    });
+    let deserialized = Picture::from_data_with_procs(&data, &mut deserial_procs).unwrap();
+    let family_names: Vec<String> = deserialized
+        .ops()
+        .into_iter()
+        .filter_map(|op| match op.op {
+            DrawOp::DrawTextBlob { blob, .. } => Some(
+                blob.runs()
+                    .next()
+                    .unwrap()
+                    .font
+                    .typeface_or_default()
+                    .family_name(),
+            ),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(family_names, [typeface.family_name()]);
+}
+
+#[test]
+fn deserialize_typeface_tables_with_procs() {
+    use crate::utils::DrawOp;
+    use crate::{Font, Paint, PictureRecorder, TextBlob, Typeface};
+
+    let typeface = Typeface::default();
+    let font = Font::from_typeface(&typeface, 12.0);
+    let blob = TextBlob::from_str("Hello", &font).unwrap();
+    let mut recorder = PictureRecorder::new();
+    let canvas = recorder.begin_recording(Rect::from_wh(100.0, 20.0), None, None);
+    canvas.draw_text_blob(&blob, (0, 12), &Paint::default());
+    let data = recorder
+        .finish_recording_as_picture(None)
+        .unwrap()
+        .serialize();
+
+    // the typeface table of the picture is deserialized by Skia.
+    let mut deserial_procs = DeserialProcs::default();
+    deserial_procs.set_typeface_proc(|_| panic!("unexpected typeface data"));
+    let deserialized = Picture::from_data_with_procs(&data, &mut deserial_procs).unwrap();
+    let blobs = deserialized
+        .ops()
+        .into_iter()
+        .filter(|op| match op.op {
+            DrawOp::DrawTextBlob { .. } => true,
+            _ => false,
+        })
+        .count();
+    assert_eq!(blobs, 1);
+}
+
+#[test]
+#[should_panic(expected = "image proc")]
+fn serialize_with_procs_resumes_panics() {
+    use crate::{PictureRecorder, Surface};
+
+    let image = Surface::new_raster_n32_premul((4, 4))
+        .unwrap()
+        .image_snapshot();
+    let mut recorder = PictureRecorder::new();
+    let canvas = recorder.begin_recording(Rect::from_wh(10.0, 10.0), None, None);
+    canvas.draw_image(&image, (1, 1), None);
+    let picture = recorder.finish_recording_as_picture(None).unwrap();
+
+    let mut serial_procs = SerialProcs::default();
+    serial_procs.set_image_proc(|_| panic!("image proc"));
+    picture.serialize_with_procs(&mut serial_procs);
+}
@@ -0,0 +1,223 @@
+use crate::prelude::*;
+use crate::{Data, Image, Picture, Typeface};
+use skia_bindings::{RustDeserialProcs, RustSerialProcs, SkData, SkImage, SkPicture, SkTypeface};
+use std::any::Any;
+use std::ffi::c_void;
+use std::panic::{self, AssertUnwindSafe};
+use std::{ptr, slice};
+
+/// Procedures that replace the serialization of the pictures, images and typefaces that
+/// are referenced by a serialized object.
+///
+/// A procedure returns `None` to use Skia's default serialization. A panic of a procedure is
+/// resumed after the serialization, the procedures are not invoked anymore until then.
+#[derive(Default)]
+pub struct SerialProcs<'a> {
+    picture: Option<Box<dyn FnMut(&Picture) -> Option<Data> + 'a>>,
+    image: Option<Box<dyn FnMut(&Image) -> Option<Data> + 'a>>,
+    typeface: Option<Box<dyn FnMut(&Typeface) -> Option<Data> + 'a>>,
+    panic: Option<Box<dyn Any + Send>>,
+}
+
+impl<'a> SerialProcs<'a> {
+    pub fn set_picture_proc(
+        &mut self,
+        picture_proc: impl FnMut(&Picture) -> Option<Data> + 'a,
+    ) -> &mut Self {
+        self.picture = Some(Box::new(picture_proc));
+        self
+    }
+
+    pub fn set_image_proc(
+        &mut self,
+        image_proc: impl FnMut(&Image) -> Option<Data> + 'a,
+    ) -> &mut Self {
+        self.image = Some(Box::new(image_proc));
+        self
+    }
+
+    /// Empty data is treated like `None`. Typefaces the procedure returns `None` for are
+    /// serialized by Skia in place of the data, so that the deserialization procedure may be
+    /// invoked with Skia's serialization, too.
+    pub fn set_typeface_proc(
+        &mut self,
+        typeface_proc: impl FnMut(&Typeface) -> Option<Data> + 'a,
+    ) -> &mut Self {
+        self.typeface = Some(Box::new(typeface_proc));
+        self
+    }
+
+    /// The returned procs refer to `self`.
+    pub(crate) fn native(&mut self) -> RustSerialProcs {
+        RustSerialProcs {
+            data: self as *mut SerialProcs as _,
+            picture: self.picture.as_ref().map(|_| serialize_picture as _),
+            image: self.image.as_ref().map(|_| serialize_image as _),
+            typeface: self.typeface.as_ref().map(|_| serialize_typeface as _),
+        }
+    }
+
+    /// Resumes the panic of a procedure that was invoked through the native procs.
+    pub(crate) fn resume_panic(&mut self) {
+        resume_panic(&mut self.panic)
+    }
+}
+
+/// Procedures that deserialize the data that was produced by `SerialProcs`.
+///
+/// A procedure returns `None` if it can not deserialize the data. Images and typefaces are
+/// then deserialized by Skia, pictures are missing from the deserialized object. A panic of a
+/// procedure is resumed after the deserialization, the procedures are not invoked anymore
+/// until then.
+#[derive(Default)]
+pub struct DeserialProcs<'a> {
+    picture: Option<Box<dyn FnMut(&[u8]) -> Option<Picture> + 'a>>,
+    image: Option<Box<dyn FnMut(&[u8]) -> Option<Image> + 'a>>,
+    typeface: Option<Box<dyn FnMut(&[u8]) -> Option<Typeface> + 'a>>,
+    panic: Option<Box<dyn Any + Send>>,
+}
+
+impl<'a> DeserialProcs<'a> {
+    pub fn set_picture_proc(
+        &mut self,
+        picture_proc: impl FnMut(&[u8]) -> Option<Picture> + 'a,
+    ) -> &mut Self {
+        self.picture = Some(Box::new(picture_proc));
+        self
+    }
+
+    /// The image procedure also receives the encoded images that were serialized by Skia.
+    pub fn set_image_proc(
+        &mut self,
+        image_proc: impl FnMut(&[u8]) -> Option<Image> + 'a,
+    ) -> &mut Self {
+        self.image = Some(Box::new(image_proc));
+        self
+    }
+
+    /// The typefaces of pictures that were serialized without a typeface procedure are
+    /// deserialized by Skia.
+    pub fn set_typeface_proc(
+        &mut self,
+        typeface_proc: impl FnMut(&[u8]) -> Option<Typeface> + 'a,
+    ) -> &mut Self {
+        self.typeface = Some(Box::new(typeface_proc));
+        self
+    }
+
+    /// The returned procs refer to `self`.
+    pub(crate) fn native(&mut self) -> RustDeserialProcs {
+        RustDeserialProcs {
+            data: self as *mut DeserialProcs as _,
+            picture: self.picture.as_ref().map(|_| deserialize_picture as _),
+            image: self.image.as_ref().map(|_| deserialize_image as _),
+            typeface: self.typeface.as_ref().map(|_| deserialize_typeface as _),
+        }
+    }
+
+    /// Resumes the panic of a procedure that was invoked through the native procs.
+    pub(crate) fn resume_panic(&mut self) {
+        resume_panic(&mut self.panic)
+    }
+}
+
+/// Hands the reference of `handle` over to the caller.
+fn into_native_ptr<N: NativeRefCounted>(handle: Option<RCHandle<N>>) -> *mut N {
+    handle
+        .map(|handle| handle.shared_native() as *const N as *mut N)
+        .unwrap_or(ptr::null_mut())
+}
+
+unsafe fn bytes<'a>(bytes: *const c_void, length: usize) -> &'a [u8] {
+    if length == 0 {
+        &[]
+    } else {
+        slice::from_raw_parts(bytes as *const u8, length)
+    }
+}
+
+/// Invokes `f` unless a procedure panicked before, and stores the panic of `f`.
+fn catch<R>(panic: &mut Option<Box<dyn Any + Send>>, f: impl FnOnce() -> Option<R>) -> Option<R> {
+    if panic.is_some() {
+        return None;
+    }
+    panic::catch_unwind(AssertUnwindSafe(f)).unwrap_or_else(|p| {
+        *panic = Some(p);
+        None
+    })
+}
+
+fn resume_panic(panic: &mut Option<Box<dyn Any + Send>>) {
+    if let Some(panic) = panic.take() {
+        panic::resume_unwind(panic)
+    }
+}
+
+unsafe extern "C" fn serialize_picture(data: *mut c_void, picture: *mut SkPicture) -> *mut SkData {
+    let procs = &mut *(data as *mut SerialProcs);
+    let picture = Picture::from_unshared_ptr(picture).unwrap();
+    let proc = &mut procs.picture;
+    into_native_ptr(catch(&mut procs.panic, || {
+        proc.as_mut().and_then(|proc| proc(&picture))
+    }))
+}
+
+unsafe extern "C" fn serialize_image(data: *mut c_void, image: *mut SkImage) -> *mut SkData {
+    let procs = &mut *(data as *mut SerialProcs);
+    let image = Image::from_unshared_ptr(image).unwrap();
+    let proc = &mut procs.image;
+    into_native_ptr(catch(&mut procs.panic, || {
+        proc.as_mut().and_then(|proc| proc(&image))
+    }))
+}
+
+unsafe extern "C" fn serialize_typeface(
+    data: *mut c_void,
+    typeface: *mut SkTypeface,
+) -> *mut SkData {
+    let procs = &mut *(data as *mut SerialProcs);
+    let typeface = Typeface::from_unshared_ptr(typeface).unwrap();
+    let proc = &mut procs.typeface;
+    into_native_ptr(catch(&mut procs.panic, || {
+        proc.as_mut().and_then(|proc| proc(&typeface))
+    }))
+}
+
+unsafe extern "C" fn deserialize_picture(
+    data: *mut c_void,
+    picture: *const c_void,
+    length: usize,
+) -> *mut SkPicture {
+    let procs = &mut *(data as *mut DeserialProcs);
+    let picture = bytes(picture, length);
+    let proc = &mut procs.picture;
+    into_native_ptr(catch(&mut procs.panic, || {
+        proc.as_mut().and_then(|proc| proc(picture))
+    }))
+}
+
+unsafe extern "C" fn deserialize_image(
+    data: *mut c_void,
+    image: *const c_void,
+    length: usize,
+) -> *mut SkImage {
+    let procs = &mut *(data as *mut DeserialProcs);
+    let image = bytes(image, length);
+    let proc = &mut procs.image;
+    into_native_ptr(catch(&mut procs.panic, || {
+        proc.as_mut().and_then(|proc| proc(image))
+    }))
+}
+
+unsafe extern "C" fn deserialize_typeface(
+    data: *mut c_void,
+    typeface: *const c_void,
+    length: usize,
+) -> *mut SkTypeface {
+    let procs = &mut *(data as *mut DeserialProcs);
+    let typeface = bytes(typeface, length);
+    let proc = &mut procs.typeface;
+    into_native_ptr(catch(&mut procs.panic, || {
+        proc.as_mut().and_then(|proc| proc(typeface))
+    }))
+}
@@ -4,10 +4,11 @@ use crate::{
 };
 use skia_bindings::{
     C_SkShader_Deserialize, C_SkShader_asAGradient, C_SkShader_isAImage,
-    C_SkShader_makeWithColorFilter, C_SkShader_makeWithLocalMatrix, SkFlattenable, SkRefCntBase,
-    SkShader, SkShader_GradientInfo, SkShader_GradientType, SkTileMode,
+    C_SkShader_makeWithColorFilter, C_SkShader_makeWithLocalMatrix, RustDeserialProcs,
+    SkFlattenable, SkRefCntBase, SkShader, SkShader_GradientInfo, SkShader_GradientType,
+    SkTileMode,
 };
-use std::mem;
+use std::{mem, ptr};
 
 #[derive(Copy, Clone, PartialEq, Eq, Debug)]
 #[repr(i32)]
@@ -65,7 +66,11 @@ impl NativeFlattenable for SkShader {
     }
 
     fn native_deserialize(data: &[u8]) -> *mut Self {
-        unsafe { C_SkShader_Deserialize(data.as_ptr() as _, data.len()) }
+        Self::native_deserialize_with_procs(data, ptr::null())
+    }
+
+    fn native_deserialize_with_procs(data: &[u8], procs: *const RustDeserialProcs) -> *mut Self {
+        unsafe { C_SkShader_Deserialize(data.as_ptr() as _, data.len(), procs) }
     }
 }
 
@@ -1,11 +1,18 @@
 use crate::prelude::*;
-use crate::{scalar, Font, GlyphId, Paint, Point, Rect, TextEncoding};
+use crate::typeface::SerializeBehavior;
+use crate::{scalar, Data, Font, GlyphId, Paint, Point, RSXform, Rect, TextEncoding};
 use skia_bindings::{
-    C_SkTextBlobBuilder_destruct, C_SkTextBlobBuilder_make, C_SkTextBlob_MakeFromText, SkTextBlob,
-    SkTextBlobBuilder,
+    C_SkTextBlobBuilder_destruct, C_SkTextBlobBuilder_make, C_SkTextBlobRunIterator_clusters,
+    C_SkTextBlobRunIterator_delete, C_SkTextBlobRunIterator_done, C_SkTextBlobRunIterator_font,
+    C_SkTextBlobRunIterator_glyphCount, C_SkTextBlobRunIterator_glyphs,
+    C_SkTextBlobRunIterator_new, C_SkTextBlobRunIterator_next, C_SkTextBlobRunIterator_offset,
+    C_SkTextBlobRunIterator_pos, C_SkTextBlobRunIterator_positioning, C_SkTextBlobRunIterator_text,
+    C_SkTextBlobRunIterator_xforms, C_SkTextBlob_Deserialize, C_SkTextBlob_MakeFromText,
+    C_SkTextBlob_serialize, SkTextBlob, SkTextBlobBuilder, SkTextBlobRunIterator,
 };
 use std::convert::TryInto;
-use std::{ptr, slice};
+use std::marker::PhantomData;
+use std::{ptr, slice, str};
 
 pub type TextBlob = RCHandle<SkTextBlob>;
 
@@ -70,6 +77,128 @@ impl RCHandle<SkTextBlob> {
             )
         })
     }
+
+    /// Serializes the text blob. The typefaces of the runs are serialized as specified by
+    /// `typeface_behavior`.
+    pub fn serialize(&self, typeface_behavior: SerializeBehavior) -> Data {
+        Data::from_ptr(unsafe {
+            C_SkTextBlob_serialize(self.native(), typeface_behavior.into_native())
+        })
+        .unwrap()
+    }
+
+    /// Recreates a text blob that was serialized with `serialize()`. Returns `None` if the data
+    /// is invalid. Typefaces that were serialized without their data are matched by family name
+    /// and style with the default font manager.
+    pub fn deserialize(data: &[u8]) -> Option<TextBlob> {
+        TextBlob::from_ptr(unsafe { C_SkTextBlob_Deserialize(data.as_ptr() as _, data.len()) })
+    }
+
+    /// Returns an iterator over the runs of the text blob.
+    pub fn runs(&self) -> TextBlobRuns {
+        TextBlobRuns {
+            native: unsafe { C_SkTextBlobRunIterator_new(self.native()) },
+            pd: PhantomData,
+        }
+    }
+}
+
+/// The glyph positions of a text blob run.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum TextBlobRunPositions<'a> {
+    /// The glyphs are positioned by the font's advances, starting at the run's offset.
+    Default,
+    /// The horizontal positions of the glyphs, the vertical position is the run's offset.
+    Horizontal(&'a [scalar]),
+    /// The positions of the glyphs, relative to the run's offset.
+    Full(&'a [Point]),
+    /// The transformations of the glyphs, relative to the run's offset.
+    RSXform(&'a [RSXform]),
+}
+
+#[derive(Copy, Clone)]
+pub struct TextBlobRun<'a> {
+    pub font: &'a Font,
+    pub glyphs: &'a [GlyphId],
+    pub offset: Point,
+    pub positions: TextBlobRunPositions<'a>,
+    /// For each glyph, the offset of the UTF-8 text it was created from, if the run was
+    /// created with text.
+    pub clusters: Option<&'a [u32]>,
+    /// The UTF-8 text of the run, if the run was created with text.
+    pub text: Option<&'a str>,
+}
+
+pub struct TextBlobRuns<'a> {
+    native: *mut SkTextBlobRunIterator,
+    pd: PhantomData<&'a TextBlob>,
+}
+
+impl<'a> Drop for TextBlobRuns<'a> {
+    fn drop(&mut self) {
+        unsafe { C_SkTextBlobRunIterator_delete(self.native) }
+    }
+}
+
+// SkTextBlobRunIterator::GlyphPositioning, which is not exposed by the bindings.
+const HORIZONTAL_POSITIONING: i32 = 1;
+const FULL_POSITIONING: i32 = 2;
+const RSXFORM_POSITIONING: i32 = 3;
+
+impl<'a> Iterator for TextBlobRuns<'a> {
+    type Item = TextBlobRun<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        unsafe {
+            let it = self.native;
+            if C_SkTextBlobRunIterator_done(it) {
+                return None;
+            }
+
+            let count = C_SkTextBlobRunIterator_glyphCount(it) as usize;
+            let glyphs = slice::from_raw_parts(C_SkTextBlobRunIterator_glyphs(it), count);
+            let mut offset = Point::default();
+            C_SkTextBlobRunIterator_offset(it, offset.native_mut());
+            let pos = C_SkTextBlobRunIterator_pos(it);
+            let positions = match C_SkTextBlobRunIterator_positioning(it) {
+                HORIZONTAL_POSITIONING => {
+                    TextBlobRunPositions::Horizontal(slice::from_raw_parts(pos, count))
+                }
+                FULL_POSITIONING => {
+                    TextBlobRunPositions::Full(slice::from_raw_parts(pos as *const Point, count))
+                }
+                RSXFORM_POSITIONING => TextBlobRunPositions::RSXform(slice::from_raw_parts(
+                    C_SkTextBlobRunIterator_xforms(it) as *const RSXform,
+                    count,
+                )),
+                _ => TextBlobRunPositions::Default,
+            };
+            let clusters = C_SkTextBlobRunIterator_clusters(it);
+            let clusters = if clusters.is_null() {
+                None
+            } else {
+                Some(slice::from_raw_parts(clusters, count))
+            };
+            let mut text_size = 0;
+            let text = C_SkTextBlobRunIterator_text(it, &mut text_size);
+            let text = if text.is_null() || text_size == 0 {
+                None
+            } else {
+                str::from_utf8(slice::from_raw_parts(text as *const u8, text_size as usize)).ok()
+            };
+
+            let run = TextBlobRun {
+                font: Font::from_native_ref(&*C_SkTextBlobRunIterator_font(it)),
+                glyphs,
+                offset,
+                positions,
+                clusters,
+                text,
+            };
+            C_SkTextBlobRunIterator_next(it);
+            Some(run)
+        }
+    }
 }
 
 pub type TextBlobBuilder = Handle<SkTextBlobBuilder>;
@@ -148,6 +277,22 @@ impl Handle<SkTextBlobBuilder> {
             )
         }
     }
+
+    pub fn alloc_run_rsxform(
+        &mut self,
+        font: &Font,
+        count: usize,
+    ) -> (&mut [GlyphId], &mut [RSXform]) {
+        unsafe {
+            let buffer = self
+                .native_mut()
+                .allocRunRSXform(font.native(), count.try_into().unwrap());
+            (
+                slice::from_raw_parts_mut((*buffer).glyphs, count),
+                slice::from_raw_parts_mut((*buffer).pos as *mut RSXform, count),
+            )
+        }
+    }
 }
 
 #[test]
@@ -155,3 +300,61 @@ fn test_point_size_equals_size_of_two_scalars_used_in_alloc_run_pos() {
     use std::mem;
     assert_eq!(mem::size_of::<Point>(), mem::size_of::<[scalar; 2]>())
 }
+
+#[test]
+fn iterate_runs() {
+    use crate::Typeface;
+
+    let font = Font::from_typeface(&Typeface::default(), 12.0);
+    let blob = TextBlob::from_str("Hello", &font).unwrap();
+    let runs: Vec<TextBlobRun> = blob.runs().collect();
+    assert_eq!(runs.len(), 1);
+    let run = &runs[0];
+    assert_eq!(run.glyphs, font.str_to_glyphs_vec("Hello").as_slice());
+    assert!(*run.font == font);
+    // TextBlob::from_str() positions the glyphs horizontally.
+    match run.positions {
+        TextBlobRunPositions::Horizontal(xs) => assert_eq!(xs.len(), 5),
+        positions => panic!("unexpected positions: {:?}", positions),
+    }
+}
+
+#[test]
+fn serialize_and_deserialize() {
+    use crate::Typeface;
+
+    let typeface = Typeface::default();
+    let font = Font::from_typeface(&typeface, 12.0);
+    let blob = TextBlob::from_str("Hello", &font).unwrap();
+    let data = blob.serialize(SerializeBehavior::DoIncludeData);
+    let deserialized = TextBlob::deserialize(&data).unwrap();
+    assert_eq!(blob.bounds(), deserialized.bounds());
+
+    let run = deserialized.runs().next().unwrap();
+    assert_eq!(run.glyphs, font.str_to_glyphs_vec("Hello").as_slice());
+    assert_eq!(
+        run.font.typeface_or_default().family_name(),
+        typeface.family_name()
+    );
+}
+
+#[test]
+fn build_rsxform_run() {
+    use crate::Typeface;
+
+    let font = Font::from_typeface(&Typeface::default(), 12.0);
+    let glyphs = font.str_to_glyphs_vec("ab");
+    let expected = [
+        RSXform::new(1.0, 0.0, (10.0, 20.0)),
+        RSXform::new(0.0, 1.0, (20.0, 20.0)),
+    ];
+    let mut builder = TextBlobBuilder::new();
+    let (run_glyphs, xforms) = builder.alloc_run_rsxform(&font, glyphs.len());
+    run_glyphs.copy_from_slice(&glyphs);
+    xforms.copy_from_slice(&expected);
+    let blob = builder.make().unwrap();
+
+    let run = blob.runs().next().unwrap();
+    assert_eq!(run.glyphs, glyphs.as_slice());
+    assert_eq!(run.positions, TextBlobRunPositions::RSXform(&expected));
+}
@@ -1,14 +1,19 @@
+use crate::font_arguments::{variation_position::Coordinate, VariationPosition};
 use crate::interop::{MemoryStream, NativeStreamBase};
 use crate::prelude::*;
-use crate::{font_arguments, interop, FontArguments};
-use crate::{font_parameters::VariationAxis, Data, FontStyle, GlyphId, Rect, Unichar};
+use crate::sfnt::fvar;
+use crate::{font_arguments, interop, FontArguments, FontMgr};
+use crate::{font_parameters::VariationAxis, Data, FontStyle, FourByteTag, GlyphId, Rect, Unichar};
 use skia_bindings::{
     C_SkTypeface_LocalizedStrings_next, C_SkTypeface_LocalizedStrings_unref,
     C_SkTypeface_MakeDefault, C_SkTypeface_MakeDeserialize, C_SkTypeface_MakeFromData,
     C_SkTypeface_MakeFromName, C_SkTypeface_isBold, C_SkTypeface_isItalic, C_SkTypeface_makeClone,
-    C_SkTypeface_serialize, SkRefCntBase, SkTypeface, SkTypeface_LocalizedStrings,
-    SkTypeface_SerializeBehavior,
+    C_SkTypeface_openStreamData, C_SkTypeface_serialize, SkRefCntBase, SkTypeface,
+    SkTypeface_LocalizedStrings, SkTypeface_SerializeBehavior,
 };
+use std::fs::File;
+use std::io::{Read, Seek};
+use std::path::Path;
 use std::{ffi, ptr};
 
 pub type FontId = skia_bindings::SkFontID;
@@ -34,6 +39,14 @@ pub struct LocalizedString {
     pub language: String,
 }
 
+/// A hashable key for a typeface at a position of its design space, see
+/// `Typeface::variation_key()`.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct VariationKey {
+    font_id: FontId,
+    coordinates: Vec<(FourByteTag, u32)>,
+}
+
 pub type Typeface = RCHandle<SkTypeface>;
 
 impl NativeRefCountedBase for SkTypeface {
@@ -107,7 +120,63 @@ impl RCHandle<SkTypeface> {
         }
     }
 
-    pub fn unique_id(self) -> FontId {
+    /// Returns the variation axes of the typeface's `fvar` table, including their names.
+    /// Returns an empty `Vec` if the typeface is not a variable font.
+    pub fn variation_axes(&self) -> Vec<fvar::Axis> {
+        fvar::Table::from_typeface(self)
+            .map(|table| table.axes)
+            .unwrap_or_default()
+    }
+
+    /// Returns the named instances of the typeface's `fvar` table, like "SemiBold Condensed".
+    pub fn named_instances(&self) -> Vec<fvar::Instance> {
+        fvar::Table::from_typeface(self)
+            .map(|table| table.instances)
+            .unwrap_or_default()
+    }
+
+    /// Creates a typeface at the given position of the design space. Axes that are
+    /// not specified are set to their default values.
+    pub fn clone_with_variation(&self, coordinates: &[Coordinate]) -> Option<Typeface> {
+        let arguments =
+            FontArguments::new().set_variation_design_position(VariationPosition { coordinates });
+        self.clone_with_arguments(&arguments)
+    }
+
+    pub fn clone_with_named_instance(&self, instance: &fvar::Instance) -> Option<Typeface> {
+        self.clone_with_variation(&instance.coordinates)
+    }
+
+    /// Returns a key that identifies the typeface at the given position of the design space.
+    ///
+    /// Unlike the unique id of the typeface returned by `clone_with_variation()`, the key is
+    /// the same for all positions that resolve to the same instance: coordinates are clamped to
+    /// their axis' range and unknown axes and coordinates that match the default are ignored.
+    /// Call this function on the typeface the variations are created from.
+    #[allow(clippy::float_cmp)]
+    pub fn variation_key(&self, coordinates: &[Coordinate]) -> VariationKey {
+        let coordinates = self
+            .variation_axes()
+            .iter()
+            .filter_map(|axis| {
+                // like Skia, the last coordinate for an axis wins.
+                let tag = axis.variation.tag;
+                let value = axis.clamp(coordinates.iter().rev().find(|c| c.axis == tag)?.value);
+                if value == axis.variation.def {
+                    None
+                } else {
+                    Some((tag, value.to_bits()))
+                }
+            })
+            .collect();
+
+        VariationKey {
+            font_id: self.unique_id(),
+            coordinates,
+        }
+    }
+
+    pub fn unique_id(&self) -> FontId {
         unsafe { self.native().uniqueID() }
     }
 
@@ -126,24 +195,60 @@ impl RCHandle<SkTypeface> {
         })
     }
 
-    // from_file is unsupported, because it is unclear what the
-    // encoding of the path name is. from_data can be used instead.
-
-    // TODO: MakeFromStream()?
+    /// Creates a typeface from the font file at `path`. The file is opened and read by Rust, so
+    /// the path does not need to be passed to Skia.
+    pub fn from_file(path: impl AsRef<Path>, index: impl Into<Option<usize>>) -> Option<Typeface> {
+        Self::from_stream(File::open(path).ok()?, index)
+    }
 
-    pub fn from_data(data: &Data, index: impl Into<Option<usize>>) {
+    pub fn from_data(data: &Data, index: impl Into<Option<usize>>) -> Option<Typeface> {
         Typeface::from_ptr(unsafe {
             C_SkTypeface_MakeFromData(
                 data.shared_native(),
                 index.into().unwrap_or_default().try_into().unwrap(),
             )
-        });
+        })
+    }
+
+    /// Creates a typeface from the font at `index` of the font data or font collection that is
+    /// read from `stream`.
+    pub fn from_stream(
+        stream: impl Read + Seek,
+        index: impl Into<Option<usize>>,
+    ) -> Option<Typeface> {
+        FontMgr::default().new_from_stream(stream, index)
+    }
+
+    /// Creates a typeface from the font data that is read from `stream`. Use `arguments` to
+    /// select the font of a collection and the design position of a variable font.
+    pub fn from_stream_with_arguments(
+        stream: impl Read + Seek,
+        arguments: &FontArguments,
+    ) -> Option<Typeface> {
+        FontMgr::default().new_from_stream_with_arguments(stream, arguments)
     }
 
     pub fn clone_with_arguments(&self, arguments: &FontArguments) -> Option<Typeface> {
         Typeface::from_ptr(unsafe { C_SkTypeface_makeClone(self.native(), arguments.native()) })
     }
 
+    /// Returns a copy of the font data this typeface was created from and the index of the font
+    /// inside of it, if the data is a font collection.
+    pub fn to_font_data(&self) -> Option<(Vec<u8>, usize)> {
+        let mut ttc_index = 0;
+        Data::from_ptr(unsafe { C_SkTypeface_openStreamData(self.native(), &mut ttc_index) })
+            .map(|data| (data.as_bytes().to_vec(), ttc_index.try_into().unwrap()))
+    }
+
+    /// Returns a copy of the data of the table `tag`, or `None` if the typeface does not
+    /// contain the table. The module `sfnt` provides parsers for common tables.
+    pub fn copy_table_data(&self, tag: FontTableTag) -> Option<Vec<u8>> {
+        let mut data = vec![0; self.get_table_size(tag)?];
+        let size = self.get_table_data(tag, &mut data);
+        data.truncate(size);
+        Some(data)
+    }
+
     // TODO: serialize(Write)?
 
     // TODO: return Data as impl Deref<[u8]> / Borrow<[u8]> here?
@@ -203,6 +308,21 @@ impl RCHandle<SkTypeface> {
         }
     }
 
+    /// Returns `true` if the typeface contains one of the color glyph tables `COLR`, `CBDT` or
+    /// `sbix`.
+    pub fn has_color_glyphs(&self) -> bool {
+        [
+            ('C', 'O', 'L', 'R'),
+            ('C', 'B', 'D', 'T'),
+            ('s', 'b', 'i', 'x'),
+        ]
+        .iter()
+        .any(|&(a, b, c, d)| {
+            self.get_table_size(*FourByteTag::from_chars(a, b, c, d))
+                .is_some()
+        })
+    }
+
     #[deprecated(note = "use get_table_data()")]
     pub fn table_data(&self, tag: FontTableTag, data: &mut [u8]) -> usize {
         self.get_table_data(tag, data)
@@ -328,3 +448,95 @@ fn family_name_iterator_owns_the_strings_and_returns_at_least_one_name_for_the_d
     }
     assert!(any);
 }
+
+#[test]
+fn load_typeface_from_data_and_stream() {
+    let tf = Typeface::default();
+    let (bytes, index) = tf.to_font_data().unwrap();
+
+    let from_data = Typeface::from_data(&Data::new_copy(&bytes), index).unwrap();
+    assert_eq!(tf.family_name(), from_data.family_name());
+
+    let from_stream = Typeface::from_stream(std::io::Cursor::new(&bytes), index).unwrap();
+    assert_eq!(tf.family_name(), from_stream.family_name());
+
+    let mut arguments = FontArguments::new();
+    arguments.set_collection_index(index);
+    let with_arguments =
+        Typeface::from_stream_with_arguments(std::io::Cursor::new(&bytes), &arguments).unwrap();
+    assert_eq!(tf.family_name(), with_arguments.family_name());
+}
+
+#[test]
+fn from_data_returns_none_for_invalid_data() {
+    assert!(Typeface::from_data(&Data::new_copy(&[0u8; 16]), None).is_none());
+    assert!(Typeface::from_stream(std::io::Cursor::new(vec![0u8; 16]), None).is_none());
+}
+
+#[test]
+fn variation_key_ignores_unknown_axes() {
+    let tf = Typeface::default();
+    let unknown = Coordinate {
+        axis: FourByteTag::from_chars('x', 'x', 'x', 'x'),
+        value: 1.0,
+    };
+    assert_eq!(tf.variation_key(&[]), tf.variation_key(&[unknown]));
+    if tf.variation_axes().is_empty() {
+        assert!(tf.named_instances().is_empty());
+    }
+}
+
+#[test]
+#[allow(clippy::float_cmp)]
+fn variations_of_a_variable_font() {
+    use crate::Font;
+
+    // a 'wght' axis from 100 to 900 with the instances "Thin", "Regular" and "Black". The
+    // advance of 'A' is 300, 500 and 900 units of 1000 at these weights.
+    let data = Data::new_copy(include_bytes!("../../resources/fonts/variable.ttf"));
+    let tf = Typeface::from_data(&data, None).unwrap();
+    let wght = FourByteTag::from_chars('w', 'g', 'h', 't');
+
+    let axes = tf.variation_axes();
+    assert_eq!(axes.len(), 1);
+    let weight = &axes[0];
+    assert_eq!(weight.variation.tag, wght);
+    let range = (
+        weight.variation.min,
+        weight.variation.def,
+        weight.variation.max,
+    );
+    assert_eq!(range, (100.0, 400.0, 900.0));
+    assert_eq!(weight.name.as_ref().map(String::as_str), Some("Weight"));
+    let parameters = tf.variation_design_parameters().unwrap();
+    assert_eq!(parameters.len(), 1);
+    assert_eq!(parameters[0].tag, wght);
+
+    let instances = tf.named_instances();
+    let names: Vec<Option<&str>> = instances
+        .iter()
+        .map(|i| i.subfamily_name.as_ref().map(String::as_str))
+        .collect();
+    assert_eq!(names, [Some("Thin"), Some("Regular"), Some("Black")]);
+    let weights: Vec<f32> = instances.iter().map(|i| i.coordinates[0].value).collect();
+    assert_eq!(weights, [100.0, 400.0, 900.0]);
+
+    let advance = |tf: &Typeface| {
+        let font = Font::from_typeface(tf, 10.0);
+        let mut widths = [0.0];
+        font.get_widths(&[font.unichar_to_glyph('A' as Unichar)], &mut widths);
+        widths[0]
+    };
+    assert_eq!(advance(&tf), 5.0);
+    let thin = tf.clone_with_named_instance(&instances[0]).unwrap();
+    assert_eq!(advance(&thin), 3.0);
+    let black = tf.clone_with_named_instance(&instances[2]).unwrap();
+    assert_eq!(advance(&black), 9.0);
+    assert_eq!(black.variation_design_position().unwrap()[0].value, 900.0);
+
+    let key = |value| tf.variation_key(&[Coordinate { axis: wght, value }]);
+    assert_ne!(key(100.0), key(900.0));
+    assert_ne!(key(700.0), tf.variation_key(&[]));
+    assert_eq!(key(400.0), tf.variation_key(&[]));
+    assert_eq!(key(900.0), key(1000.0));
+}
@@ -0,0 +1,121 @@
+use crate::prelude::*;
+use crate::{FontMgr, Typeface};
+use skia_bindings::{C_TypefaceFontProvider_new, C_TypefaceFontProvider_registerTypeface};
+use std::ffi::CString;
+use std::ops::Deref;
+use std::ptr;
+
+/// A font manager that resolves font families only against the typefaces that were
+/// registered with it and never falls back to the fonts that are installed on the system.
+///
+/// Use it to render text independently of the platform's font configuration.
+///
+/// `match_family_style_character()` prefers typefaces with color glyphs if the language tag
+/// `und-Zsye` is passed in `bcp_47`, so that emoji are resolved to a registered color emoji
+/// font.
+pub struct TypefaceFontProvider(FontMgr);
+
+impl Default for TypefaceFontProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Deref for TypefaceFontProvider {
+    type Target = FontMgr;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl From<TypefaceFontProvider> for FontMgr {
+    fn from(provider: TypefaceFontProvider) -> Self {
+        provider.0
+    }
+}
+
+impl TypefaceFontProvider {
+    pub fn new() -> Self {
+        TypefaceFontProvider(FontMgr::from_ptr(unsafe { C_TypefaceFontProvider_new() }).unwrap())
+    }
+
+    /// Registers the typeface under the family name `alias`, or under the typeface's own family
+    /// name if `alias` is `None`. Typefaces that are registered under the same family name become
+    /// styles of that family.
+    ///
+    /// Returns the number of typefaces registered, which is 0 if the family name is empty.
+    pub fn register_typeface(&mut self, typeface: Typeface, alias: Option<&str>) -> usize {
+        let alias = alias.map(|alias| CString::new(alias).unwrap());
+        unsafe {
+            C_TypefaceFontProvider_registerTypeface(
+                self.0.native_mut(),
+                typeface.shared_native() as *const _ as *mut _,
+                alias.as_ref().map(|a| a.as_ptr()).unwrap_or(ptr::null()),
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TypefaceFontProvider;
+    use crate::{FontMgr, FontStyle, Typeface};
+
+    #[test]
+    fn resolves_only_registered_families() {
+        let typeface = Typeface::default();
+        let system_family = typeface.family_name();
+
+        let mut provider = TypefaceFontProvider::new();
+        assert_eq!(provider.register_typeface(typeface, Some("Hermetic")), 1);
+
+        let font_mgr: FontMgr = provider.into();
+        assert_eq!(font_mgr.count_families(), 1);
+        assert_eq!(font_mgr.family_name(0), "Hermetic");
+        assert!(font_mgr
+            .match_family_style("Hermetic", FontStyle::normal())
+            .is_some());
+        assert!(font_mgr
+            .match_family_style(&system_family, FontStyle::normal())
+            .is_none());
+        assert_eq!(font_mgr.match_family(&system_family).count(), 0);
+    }
+
+    #[test]
+    fn match_character_falls_back_to_registered_families() {
+        let mut provider = TypefaceFontProvider::default();
+        provider.register_typeface(Typeface::default(), None);
+
+        let typeface = provider
+            .match_family_style_character("Unknown", FontStyle::normal(), &[], 'A' as i32)
+            .unwrap();
+        assert_eq!(typeface.family_name(), Typeface::default().family_name());
+    }
+
+    #[test]
+    fn match_character_prefers_color_typefaces_for_emoji() {
+        use crate::Data;
+
+        // a CBDT font with a color glyph for U+25A0.
+        let data = Data::new_copy(include_bytes!("../../resources/fonts/color_glyphs.ttf"));
+        let color = Typeface::from_data(&data, None).unwrap();
+        let plain = FontMgr::default()
+            .match_family_style_character("", FontStyle::normal(), &[], 0x25a0)
+            .filter(|typeface| !typeface.has_color_glyphs())
+            .expect("a system typeface without color glyphs that covers U+25A0");
+
+        let mut provider = TypefaceFontProvider::new();
+        provider.register_typeface(plain.clone(), Some("Plain"));
+        provider.register_typeface(color.clone(), Some("Color"));
+
+        let emoji = provider
+            .match_family_style_character("Plain", FontStyle::normal(), &["und-Zsye"], 0x25a0)
+            .unwrap();
+        assert_eq!(emoji.unique_id(), color.unique_id());
+        let text = provider
+            .match_family_style_character("Plain", FontStyle::normal(), &[], 0x25a0)
+            .unwrap();
+        assert_eq!(text.unique_id(), plain.unique_id());
+    }
+}
@@ -11,7 +11,7 @@ pub(crate) type u8cpu = skia_bindings::U8CPU;
 // FourByteTag
 //
 
-#[derive(Copy, Clone, PartialEq, Eq, Default, Debug)]
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Default, Debug)]
 #[repr(transparent)]
 pub struct FourByteTag(SkFourByteTag);
 
@@ -36,6 +36,15 @@ impl From<u32> for FourByteTag {
 }
 
 impl FourByteTag {
+    pub const fn from_chars(a: char, b: char, c: char, d: char) -> Self {
+        FourByteTag(
+            (a as u32 & 0xff) << 24
+                | (b as u32 & 0xff) << 16
+                | (c as u32 & 0xff) << 8
+                | (d as u32 & 0xff),
+        )
+    }
+
     pub fn a(self) -> u8 {
         (self.into_native() >> 24) as u8
     }
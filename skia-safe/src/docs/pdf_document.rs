@@ -1,14 +1,129 @@
 pub mod pdf {
-    use crate::interop::DynamicMemoryWStream;
+    use crate::document::output::{self, OutputStream};
+    use crate::document::state;
     use crate::prelude::*;
-    use crate::{scalar, DateTime, Document};
+    use crate::{scalar, Canvas, DateTime, Document, WriterDocument};
     use skia_bindings::{
         C_SkPDF_MakeDocument, C_SkPDF_Metadata_Construct, C_SkPDF_Metadata_destruct,
-        SkPDF_Metadata, SkString,
+        C_SkPDF_SetNodeId, SkPDF_DocumentStructureType, SkPDF_Metadata, SkPDF_StructureElementNode,
+        SkString,
     };
+    use std::io::Write;
+    use std::ptr;
 
-    // TODO: DocumentStructureType
-    // TODO: StructureElementNode
+    /// The structure types of the PDF specification, section 14.8.4.
+    #[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+    #[repr(i32)]
+    pub enum DocumentStructureType {
+        Document = SkPDF_DocumentStructureType::kDocument as _,
+        Part = SkPDF_DocumentStructureType::kPart as _,
+        Art = SkPDF_DocumentStructureType::kArt as _,
+        Sect = SkPDF_DocumentStructureType::kSect as _,
+        Div = SkPDF_DocumentStructureType::kDiv as _,
+        BlockQuote = SkPDF_DocumentStructureType::kBlockQuote as _,
+        Caption = SkPDF_DocumentStructureType::kCaption as _,
+        TOC = SkPDF_DocumentStructureType::kTOC as _,
+        TOCI = SkPDF_DocumentStructureType::kTOCI as _,
+        Index = SkPDF_DocumentStructureType::kIndex as _,
+        NonStruct = SkPDF_DocumentStructureType::kNonStruct as _,
+        Private = SkPDF_DocumentStructureType::kPrivate as _,
+        H = SkPDF_DocumentStructureType::kH as _,
+        H1 = SkPDF_DocumentStructureType::kH1 as _,
+        H2 = SkPDF_DocumentStructureType::kH2 as _,
+        H3 = SkPDF_DocumentStructureType::kH3 as _,
+        H4 = SkPDF_DocumentStructureType::kH4 as _,
+        H5 = SkPDF_DocumentStructureType::kH5 as _,
+        H6 = SkPDF_DocumentStructureType::kH6 as _,
+        P = SkPDF_DocumentStructureType::kP as _,
+        L = SkPDF_DocumentStructureType::kL as _,
+        LI = SkPDF_DocumentStructureType::kLI as _,
+        Lbl = SkPDF_DocumentStructureType::kLbl as _,
+        LBody = SkPDF_DocumentStructureType::kLBody as _,
+        Table = SkPDF_DocumentStructureType::kTable as _,
+        TR = SkPDF_DocumentStructureType::kTR as _,
+        TH = SkPDF_DocumentStructureType::kTH as _,
+        TD = SkPDF_DocumentStructureType::kTD as _,
+        THead = SkPDF_DocumentStructureType::kTHead as _,
+        TBody = SkPDF_DocumentStructureType::kTBody as _,
+        TFoot = SkPDF_DocumentStructureType::kTFoot as _,
+        Span = SkPDF_DocumentStructureType::kSpan as _,
+        Quote = SkPDF_DocumentStructureType::kQuote as _,
+        Note = SkPDF_DocumentStructureType::kNote as _,
+        Reference = SkPDF_DocumentStructureType::kReference as _,
+        BibEntry = SkPDF_DocumentStructureType::kBibEntry as _,
+        Code = SkPDF_DocumentStructureType::kCode as _,
+        Link = SkPDF_DocumentStructureType::kLink as _,
+        Annot = SkPDF_DocumentStructureType::kAnnot as _,
+        Ruby = SkPDF_DocumentStructureType::kRuby as _,
+        RB = SkPDF_DocumentStructureType::kRB as _,
+        RT = SkPDF_DocumentStructureType::kRT as _,
+        RP = SkPDF_DocumentStructureType::kRP as _,
+        Warichu = SkPDF_DocumentStructureType::kWarichu as _,
+        WT = SkPDF_DocumentStructureType::kWT as _,
+        WP = SkPDF_DocumentStructureType::kWP as _,
+        Figure = SkPDF_DocumentStructureType::kFigure as _,
+        Formula = SkPDF_DocumentStructureType::kFormula as _,
+        Form = SkPDF_DocumentStructureType::kForm as _,
+    }
+
+    impl NativeTransmutable<SkPDF_DocumentStructureType> for DocumentStructureType {}
+    #[test]
+    fn test_document_structure_type_layout() {
+        DocumentStructureType::test_layout()
+    }
+
+    /// A node in the structure element tree of a tagged PDF.
+    ///
+    /// Drawing commands are assigned to a node by calling `set_node_id()` with the node's id
+    /// before drawing. Note that m75's structure element nodes do not support alternate text.
+    #[derive(Clone, PartialEq, Eq, Debug)]
+    pub struct StructureElementNode {
+        pub node_type: DocumentStructureType,
+        /// The id of the node, must be unique in the tree.
+        pub node_id: i32,
+        pub children: Vec<StructureElementNode>,
+    }
+
+    impl StructureElementNode {
+        pub fn new(node_type: DocumentStructureType, node_id: i32) -> StructureElementNode {
+            StructureElementNode {
+                node_type,
+                node_id,
+                children: Vec::new(),
+            }
+        }
+
+        pub fn with_children(
+            mut self,
+            children: impl IntoIterator<Item = StructureElementNode>,
+        ) -> Self {
+            self.children.extend(children);
+            self
+        }
+
+        // The native nodes refer to their children, so the children are stored in `storage`,
+        // which must outlive the returned node.
+        fn native(
+            &self,
+            storage: &mut Vec<Vec<SkPDF_StructureElementNode>>,
+        ) -> SkPDF_StructureElementNode {
+            let children: Vec<SkPDF_StructureElementNode> =
+                self.children.iter().map(|c| c.native(storage)).collect();
+            let node = SkPDF_StructureElementNode {
+                fChildren: if children.is_empty() {
+                    ptr::null()
+                } else {
+                    children.as_ptr()
+                },
+                fChildCount: children.len(),
+                fNodeId: self.node_id,
+                fType: self.node_type.into_native(),
+            };
+            // note: moving the Vec does not move its elements.
+            storage.push(children);
+            node
+        }
+    }
 
     #[derive(Clone, Debug, Default)]
     pub struct Metadata {
@@ -23,12 +138,34 @@ pub mod pdf {
         pub raster_dpi: Option<scalar>,
         pub pdfa: bool,
         pub encoding_quality: Option<i32>,
-        // TODO: fStructureElementTreeRoot
+        /// The root of the structure element tree, required for tagged PDFs.
+        pub structure_element_tree_root: Option<StructureElementNode>,
     }
 
-    // TODO: SetNodeId
+    /// Associates the drawing commands that follow with the structure element node `node_id`.
+    ///
+    /// `canvas` must be the canvas of a page of a PDF document.
+    pub fn set_node_id(canvas: &mut Canvas, node_id: i32) {
+        unsafe { C_SkPDF_SetNodeId(canvas.native_mut(), node_id) }
+    }
 
+    /// Creates a PDF document that is written to memory and returned by `Document::close()`.
     pub fn new_document(metadata: Option<&Metadata>) -> Document {
+        new_document_to_output(output::Memory::new(), metadata)
+    }
+
+    /// Creates a PDF document that streams its output to `writer`.
+    pub fn new_document_to_writer<'a>(
+        writer: impl Write + 'a,
+        metadata: Option<&Metadata>,
+    ) -> WriterDocument<'a> {
+        new_document_to_output(output::Writer::new(writer), metadata)
+    }
+
+    fn new_document_to_output<O: OutputStream>(
+        mut output: O,
+        metadata: Option<&Metadata>,
+    ) -> Document<state::Open, O> {
         let mut md = InternalMetadata::default();
         if let Some(metadata) = metadata {
             let internal = md.native_mut();
@@ -53,15 +190,20 @@ pub mod pdf {
             }
         }
 
-        // we can't move the memory stream around anymore as soon it's referred by
-        // the document.
-        let mut memory_stream = Box::pin(DynamicMemoryWStream::new());
-        let document = RCHandle::from_ptr(unsafe {
-            C_SkPDF_MakeDocument(&mut memory_stream.native_mut()._base, md.native())
-        })
-        .unwrap();
+        // the document copies the structure element tree when it's created.
+        let mut tree_storage = Vec::new();
+        let tree_root = metadata
+            .and_then(|m| m.structure_element_tree_root.as_ref())
+            .map(|root| root.native(&mut tree_storage));
+        if let Some(tree_root) = &tree_root {
+            md.native_mut().fStructureElementTreeRoot = tree_root;
+        }
+
+        let document =
+            RCHandle::from_ptr(unsafe { C_SkPDF_MakeDocument(output.stream_mut(), md.native()) })
+                .unwrap();
 
-        Document::new(memory_stream, document)
+        Document::new(output, document)
     }
 
     //
@@ -91,4 +233,40 @@ pub mod pdf {
             unsafe { self.set2(bytes.as_ptr() as _, bytes.len()) }
         }
     }
+
+    #[test]
+    fn write_document_to_writer() {
+        let mut bytes = Vec::new();
+        {
+            let mut document = new_document_to_writer(&mut bytes, None).begin_page((10, 10), None);
+            document.canvas().clear(crate::Color::RED);
+            document.end_page().close().unwrap();
+        }
+        assert!(bytes.starts_with(b"%PDF"));
+    }
+
+    #[test]
+    fn tagged_document() {
+        let root =
+            StructureElementNode::new(DocumentStructureType::Document, 1).with_children(vec![
+                StructureElementNode::new(DocumentStructureType::H1, 2),
+                StructureElementNode::new(DocumentStructureType::P, 3),
+            ]);
+        let metadata = Metadata {
+            structure_element_tree_root: Some(root),
+            ..Metadata::default()
+        };
+
+        let mut document = new_document(Some(&metadata)).begin_page((100, 100), None);
+        let canvas = document.canvas();
+        set_node_id(canvas, 2);
+        canvas.draw_circle((20, 20), 10.0, &crate::Paint::default());
+        set_node_id(canvas, 3);
+        canvas.draw_circle((60, 60), 10.0, &crate::Paint::default());
+        let data = document.end_page().close();
+
+        let contents = String::from_utf8_lossy(data.as_bytes());
+        assert!(contents.contains("/StructTreeRoot"));
+        assert!(contents.contains("/H1"));
+    }
 }
@@ -6,13 +6,16 @@
 use crate::prelude::*;
 use crate::Data;
 use skia_bindings::{
-    C_SkDynamicMemoryWStream_Construct, C_SkDynamicMemoryWStream_detachAsData,
-    C_SkDynamicMemoryWStream_detachAsStream, C_SkMemoryStream_MakeDirect, C_SkStream_delete,
-    C_SkWStream_destruct, C_SkWStream_write, SkDynamicMemoryWStream, SkMemoryStream, SkStream,
-    SkStreamAsset,
+    C_RustStream_new, C_RustWStream_new, C_SkDynamicMemoryWStream_Construct,
+    C_SkDynamicMemoryWStream_detachAsData, C_SkDynamicMemoryWStream_detachAsStream,
+    C_SkMemoryStream_MakeDirect, C_SkStream_delete, C_SkWStream_delete, C_SkWStream_destruct,
+    C_SkWStream_write, SkDynamicMemoryWStream, SkMemoryStream, SkStream, SkStreamAsset, SkWStream,
 };
+use std::ffi::c_void;
+use std::io::{self, Read, Seek, SeekFrom, Write};
 use std::marker::PhantomData;
-use std::ptr;
+use std::panic::{self, AssertUnwindSafe};
+use std::{mem, ptr, slice};
 
 /// Trait representing an Skia allocated Stream type with a base class of SkStream.
 pub struct Stream<N: NativeStreamBase>(*mut N);
@@ -127,6 +130,186 @@ impl Handle<SkDynamicMemoryWStream> {
     }
 }
 
+/// A native stream asset that forwards all reads and seeks to a Rust stream.
+///
+/// The Rust stream is owned by the native stream and dropped with it, so ownership of a
+/// `RustStream` can be passed to Skia functions that expect a `std::unique_ptr<SkStream>`.
+///
+/// The native stream can not be duplicated or forked, so native code that reads from a
+/// duplicate of its stream fails.
+pub struct RustStream<'a> {
+    stream: StreamAsset,
+    pd: PhantomData<&'a mut ()>,
+}
+
+impl<'a> RustStream<'a> {
+    /// Wraps a Rust stream. The native stream's position 0 refers to the current position of
+    /// the Rust stream.
+    pub fn new<T: Read + Seek + 'a>(mut stream: T) -> io::Result<RustStream<'a>> {
+        let start = stream.seek(SeekFrom::Current(0))?;
+        let end = stream.seek(SeekFrom::End(0))?;
+        stream.seek(SeekFrom::Start(start))?;
+
+        let data = Box::into_raw(Box::new(RustStreamData { start, stream }));
+        let native = unsafe {
+            C_RustStream_new(
+                data as _,
+                end.saturating_sub(start).try_into().unwrap(),
+                Some(read_trampoline::<T>),
+                Some(seek_trampoline::<T>),
+                Some(drop_trampoline::<T>),
+            )
+        };
+
+        Ok(RustStream {
+            stream: StreamAsset::from_ptr(native),
+            pd: PhantomData,
+        })
+    }
+
+    pub fn stream_mut(&mut self) -> &mut SkStream {
+        self.stream.native_mut().as_stream_mut()
+    }
+
+    /// Releases the native stream. The caller is responsible for deleting it.
+    pub fn into_native(mut self) -> *mut SkStream {
+        let ptr = self.stream_mut() as *mut SkStream;
+        mem::forget(self);
+        ptr
+    }
+}
+
+struct RustStreamData<T> {
+    // the position of the Rust stream the native stream's position 0 refers to.
+    start: u64,
+    stream: T,
+}
+
+// Panics must not unwind into Skia, so a panicking stream is treated like a stream that
+// fails to read or seek.
+
+unsafe extern "C" fn read_trampoline<T: Read>(
+    data: *mut c_void,
+    buffer: *mut c_void,
+    count: usize,
+) -> usize {
+    let stream = &mut (*(data as *mut RustStreamData<T>)).stream;
+    let buffer = slice::from_raw_parts_mut(buffer as *mut u8, count);
+    // SkStream::read() returns less than the requested bytes only at the end of the stream.
+    let mut bytes_read = 0;
+    let _ = panic::catch_unwind(AssertUnwindSafe(|| {
+        while bytes_read < count {
+            match stream.read(&mut buffer[bytes_read..]) {
+                Ok(0) => break,
+                Ok(n) => bytes_read += n,
+                Err(ref e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(_) => break,
+            }
+        }
+    }));
+    bytes_read
+}
+
+unsafe extern "C" fn seek_trampoline<T: Seek>(data: *mut c_void, position: usize) -> bool {
+    let data = &mut *(data as *mut RustStreamData<T>);
+    let position = data.start + position as u64;
+    panic::catch_unwind(AssertUnwindSafe(|| {
+        data.stream.seek(SeekFrom::Start(position)).is_ok()
+    }))
+    .unwrap_or(false)
+}
+
+unsafe extern "C" fn drop_trampoline<T>(data: *mut c_void) {
+    let data = Box::from_raw(data as *mut RustStreamData<T>);
+    let _ = panic::catch_unwind(AssertUnwindSafe(|| drop(data)));
+}
+
+/// A native write stream that forwards all writes to a Rust writer.
+///
+/// Skia ignores write errors, so the first I/O error is kept and all subsequent writes fail.
+pub struct RustWStream<'a> {
+    native: *mut SkWStream,
+    // boxed, because the native stream refers to it.
+    writer: Box<RustWriter<'a>>,
+}
+
+struct RustWriter<'a> {
+    writer: Box<dyn Write + 'a>,
+    error: Option<io::Error>,
+}
+
+impl<'a> Drop for RustWStream<'a> {
+    fn drop(&mut self) {
+        unsafe { C_SkWStream_delete(self.native) }
+    }
+}
+
+impl<'a> RustWStream<'a> {
+    pub fn new(writer: impl Write + 'a) -> RustWStream<'a> {
+        let mut writer = Box::new(RustWriter {
+            writer: Box::new(writer),
+            error: None,
+        });
+        let native = unsafe {
+            C_RustWStream_new(
+                writer.as_mut() as *mut RustWriter as _,
+                Some(write_trampoline),
+                Some(flush_trampoline),
+            )
+        };
+        assert_ne!(native, ptr::null_mut());
+        RustWStream { native, writer }
+    }
+
+    pub fn stream_mut(&mut self) -> &mut SkWStream {
+        unsafe { &mut *self.native }
+    }
+
+    /// Flushes the writer and returns the first I/O error that occurred.
+    pub fn finish(&mut self) -> io::Result<()> {
+        unsafe { flush_trampoline(self.writer.as_mut() as *mut RustWriter as _) };
+        match self.writer.error.take() {
+            Some(error) => Err(error),
+            None => Ok(()),
+        }
+    }
+}
+
+// A panicking writer is treated like a writer that fails with an error.
+
+unsafe extern "C" fn write_trampoline(
+    data: *mut c_void,
+    buffer: *const c_void,
+    count: usize,
+) -> bool {
+    let writer = &mut *(data as *mut RustWriter);
+    if writer.error.is_some() {
+        return false;
+    }
+    let buffer = slice::from_raw_parts(buffer as *const u8, count);
+    match catch_panic(|| writer.writer.write_all(buffer)) {
+        Ok(()) => true,
+        Err(error) => {
+            writer.error = Some(error);
+            false
+        }
+    }
+}
+
+unsafe extern "C" fn flush_trampoline(data: *mut c_void) {
+    let writer = &mut *(data as *mut RustWriter);
+    if writer.error.is_none() {
+        if let Err(error) = catch_panic(|| writer.writer.flush()) {
+            writer.error = Some(error);
+        }
+    }
+}
+
+fn catch_panic(f: impl FnOnce() -> io::Result<()>) -> io::Result<()> {
+    panic::catch_unwind(AssertUnwindSafe(f))
+        .unwrap_or_else(|_| Err(io::Error::new(io::ErrorKind::Other, "writer panicked")))
+}
+
 #[test]
 fn detaching_empty_dynamic_memory_w_stream_leads_to_non_null_data() {
     let mut stream = DynamicMemoryWStream::new();
@@ -139,3 +322,83 @@ fn memory_stream_from_bytes() {
     let stream = MemoryStream::from_bytes(&[1, 2, 3]);
     drop(stream);
 }
+
+#[test]
+fn rust_stream_drops_the_rust_stream() {
+    use std::io::Cursor;
+    use std::rc::Rc;
+
+    let bytes = Rc::new(vec![1u8, 2, 3]);
+    let stream = RustStream::new(Cursor::new(AsRefRc(bytes.clone()))).unwrap();
+    assert_eq!(2, Rc::strong_count(&bytes));
+    drop(stream);
+    assert_eq!(1, Rc::strong_count(&bytes));
+
+    struct AsRefRc(Rc<Vec<u8>>);
+    impl AsRef<[u8]> for AsRefRc {
+        fn as_ref(&self) -> &[u8] {
+            &self.0
+        }
+    }
+}
+
+#[test]
+fn rust_stream_reports_panics_as_read_errors() {
+    use crate::Codec;
+
+    struct Panicking;
+    impl Read for Panicking {
+        fn read(&mut self, _: &mut [u8]) -> io::Result<usize> {
+            panic!("read")
+        }
+    }
+    impl Seek for Panicking {
+        fn seek(&mut self, _: SeekFrom) -> io::Result<u64> {
+            Ok(4)
+        }
+    }
+
+    assert!(Codec::from_stream(Panicking).is_none());
+}
+
+#[test]
+fn rust_w_stream_writes_to_the_rust_writer() {
+    let mut bytes = Vec::new();
+    {
+        let mut stream = RustWStream::new(&mut bytes);
+        assert!(unsafe { C_SkWStream_write(stream.stream_mut(), [1u8, 2, 3].as_ptr() as _, 3) });
+        assert!(stream.finish().is_ok());
+    }
+    assert_eq!(vec![1u8, 2, 3], bytes);
+}
+
+#[test]
+fn rust_w_stream_keeps_the_first_error() {
+    let mut buffer = [0u8; 2];
+    let mut stream = RustWStream::new(&mut buffer[..]);
+    let native = stream.stream_mut();
+    assert!(!unsafe { C_SkWStream_write(native, [1u8, 2, 3].as_ptr() as _, 3) });
+    assert!(!unsafe { C_SkWStream_write(native, [4u8].as_ptr() as _, 1) });
+    assert_eq!(
+        io::ErrorKind::WriteZero,
+        stream.finish().unwrap_err().kind()
+    );
+    assert!(stream.finish().is_ok());
+}
+
+#[test]
+fn rust_w_stream_reports_panics_as_errors() {
+    struct Panicking;
+    impl Write for Panicking {
+        fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+            panic!("write")
+        }
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let mut stream = RustWStream::new(Panicking);
+    assert!(!unsafe { C_SkWStream_write(stream.stream_mut(), [1u8].as_ptr() as _, 1) });
+    assert_eq!(io::ErrorKind::Other, stream.finish().unwrap_err().kind());
+}
@@ -1,11 +1,18 @@
-mod codec;
+pub mod codec;
 mod core;
 mod docs;
 mod effects;
 pub mod gpu;
 mod interop;
+#[cfg(feature = "particles")]
+pub mod particles;
 mod pathops;
 mod prelude;
+pub mod sfnt;
+#[cfg(feature = "shaper")]
+pub mod shaper;
+#[cfg(feature = "animation")]
+pub mod skottie;
 #[cfg(feature = "svg")]
 pub mod svg;
 // TODO: We don't export utils/* into the crate's root yet. Should we?
@@ -20,7 +27,7 @@ extern crate lazy_static;
 pub use crate::prelude::Borrows;
 
 /// All Sk* types are accessible via skia_safe::
-pub use crate::codec::*;
+pub use crate::codec::{AndroidCodec, Codec, EncodedOrigin};
 pub use crate::core::*;
 pub use crate::docs::*;
 pub use crate::effects::*;
@@ -0,0 +1,2 @@
+mod particle_effect;
+pub use self::particle_effect::*;
@@ -0,0 +1,145 @@
+use crate::prelude::*;
+use crate::{scalar, Canvas, Point};
+use skia_bindings::{
+    C_SkParticleEffectParams_MakeFromJSON, C_SkParticleEffectParams_getRate,
+    C_SkParticleEffectParams_setRate, C_SkParticleEffect_Make, C_SkParticleEffect_draw,
+    C_SkParticleEffect_getCount, C_SkParticleEffect_isAlive, C_SkParticleEffect_start,
+    C_SkParticleEffect_update, SkParticleEffect, SkParticleEffectParams, SkRefCntBase,
+};
+
+impl NativeRefCountedBase for SkParticleEffectParams {
+    type Base = SkRefCntBase;
+
+    fn ref_counted_base(&self) -> &Self::Base {
+        &self._base._base
+    }
+}
+
+impl NativeRefCountedBase for SkParticleEffect {
+    type Base = SkRefCntBase;
+
+    fn ref_counted_base(&self) -> &Self::Base {
+        &self._base._base
+    }
+}
+
+/// A running instance of a particle effect.
+pub struct ParticleEffect {
+    effect: RCHandle<SkParticleEffect>,
+    // every effect owns its parameters, so that the rate can be changed per effect.
+    params: RCHandle<SkParticleEffectParams>,
+    position: Point,
+}
+
+impl ParticleEffect {
+    /// Loads the effect parameters from their JSON representation and creates an effect that
+    /// uses `seed` to initialize its random number generator.
+    pub fn from_json(json: &str, seed: u32) -> Option<ParticleEffect> {
+        let params = RCHandle::from_ptr(unsafe {
+            C_SkParticleEffectParams_MakeFromJSON(json.as_ptr() as _, json.len())
+        })?;
+        let effect = RCHandle::from_ptr(unsafe {
+            C_SkParticleEffect_Make(params.native_mut_force(), seed)
+        })?;
+        Some(ParticleEffect {
+            effect,
+            params,
+            position: Point::default(),
+        })
+    }
+
+    /// Starts the effect at the time `now` in seconds.
+    pub fn start(&mut self, now: f64, looping: bool) -> &mut Self {
+        unsafe { C_SkParticleEffect_start(self.effect.native_mut(), now, looping) }
+        self
+    }
+
+    /// Spawns, updates, and removes particles up to the time `now` in seconds.
+    pub fn update(&mut self, now: f64) -> &mut Self {
+        unsafe { C_SkParticleEffect_update(self.effect.native_mut(), now) }
+        self
+    }
+
+    /// Draws the particles at the position of the effect.
+    pub fn draw(&mut self, canvas: &mut Canvas) {
+        canvas.save();
+        canvas.translate(self.position);
+        unsafe { C_SkParticleEffect_draw(self.effect.native_mut(), canvas.native_mut()) }
+        canvas.restore();
+    }
+
+    pub fn is_alive(&self) -> bool {
+        unsafe { C_SkParticleEffect_isAlive(self.effect.native()) }
+    }
+
+    /// The number of living particles.
+    pub fn count(&self) -> usize {
+        unsafe { C_SkParticleEffect_getCount(self.effect.native()) }
+            .try_into()
+            .unwrap()
+    }
+
+    pub fn position(&self) -> Point {
+        self.position
+    }
+
+    /// Sets the position the particles are drawn relative to.
+    pub fn set_position(&mut self, position: impl Into<Point>) -> &mut Self {
+        self.position = position.into();
+        self
+    }
+
+    /// The number of particles spawned per second.
+    pub fn rate(&self) -> scalar {
+        unsafe { C_SkParticleEffectParams_getRate(self.params.native()) }
+    }
+
+    pub fn set_rate(&mut self, rate: scalar) -> &mut Self {
+        unsafe { C_SkParticleEffectParams_setRate(self.params.native_mut(), rate) }
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ParticleEffect;
+    use crate::{Point, Surface};
+
+    const CIRCLES: &str = r#"{
+        "MaxCount": 100,
+        "Duration": 1,
+        "Rate": 50,
+        "Drawable": { "Type": "SkCircleDrawable", "Radius": 2 },
+        "Spawn": [],
+        "Update": []
+    }"#;
+
+    #[test]
+    fn spawn_and_draw_particles() {
+        let mut effect = ParticleEffect::from_json(CIRCLES, 0).unwrap();
+        assert!(!effect.is_alive());
+        effect.start(0.0, false).update(0.5);
+        assert!(effect.is_alive());
+        assert!(effect.count() > 0);
+
+        effect.set_position((10, 10));
+        assert_eq!(Point::new(10.0, 10.0), effect.position());
+        let mut surface = Surface::new_raster_n32_premul((20, 20)).unwrap();
+        effect.draw(surface.canvas());
+    }
+
+    #[test]
+    #[allow(clippy::float_cmp)]
+    fn rate_is_per_effect() {
+        let mut a = ParticleEffect::from_json(CIRCLES, 0).unwrap();
+        let b = ParticleEffect::from_json(CIRCLES, 0).unwrap();
+        a.set_rate(10.0);
+        assert_eq!(10.0, a.rate());
+        assert_eq!(50.0, b.rate());
+    }
+
+    #[test]
+    fn invalid_json() {
+        assert!(ParticleEffect::from_json("[]", 0).is_none());
+    }
+}
@@ -0,0 +1,14 @@
+//! Parsers for OpenType font tables that Skia does not expose.
+//!
+//! The tables can be parsed from raw table data, or loaded from a `Typeface` with
+//! `from_typeface()`.
+
+pub mod cmap;
+pub mod color;
+pub mod fvar;
+pub mod layout;
+pub mod name;
+pub mod os2;
+pub mod post;
+
+mod reader;
@@ -0,0 +1,249 @@
+//! The character to glyph index mapping table `cmap`.
+//!
+//! Only the Unicode coverage is parsed, use `Typeface::unichar_to_glyph()` to map characters
+//! to glyphs.
+
+use super::reader::Reader;
+use crate::{FourByteTag, Typeface, Unichar};
+use std::ops::RangeInclusive;
+
+pub const TAG: FourByteTag = FourByteTag::from_chars('c', 'm', 'a', 'p');
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct EncodingRecord {
+    pub platform_id: u16,
+    pub encoding_id: u16,
+    /// The format of the subtable, 0 if it could not be read.
+    pub format: u16,
+}
+
+#[derive(Clone, PartialEq, Eq, Default, Debug)]
+pub struct Table {
+    pub encodings: Vec<EncodingRecord>,
+    /// The sorted and non-overlapping ranges of the characters that are mapped to a glyph.
+    /// Empty if the table has no Unicode subtable in the formats 4 or 12.
+    pub coverage: Vec<RangeInclusive<Unichar>>,
+}
+
+impl Table {
+    pub fn from_typeface(typeface: &Typeface) -> Option<Table> {
+        Self::parse(&typeface.copy_table_data(*TAG)?)
+    }
+
+    pub fn parse(data: &[u8]) -> Option<Table> {
+        let data = Reader::new(data);
+        let count = usize::from(data.u16(2)?);
+
+        let mut encodings = Vec::with_capacity(count);
+        let mut best: Option<(u32, Reader)> = None;
+        for i in 0..count {
+            let platform_id = data.u16(4 + i * 8)?;
+            let encoding_id = data.u16(6 + i * 8)?;
+            let subtable = data.at(data.u32(8 + i * 8)? as usize);
+            let format = subtable.and_then(|s| s.u16(0)).unwrap_or(0);
+            encodings.push(EncodingRecord {
+                platform_id,
+                encoding_id,
+                format,
+            });
+
+            // prefer full Unicode subtables over BMP only subtables.
+            let rank = match (platform_id, encoding_id, format) {
+                (3, 10, 12) | (0, 4, 12) | (0, 6, 12) => 2,
+                (3, 1, 4) | (0, _, 4) => 1,
+                _ => 0,
+            };
+            if let Some(subtable) = subtable {
+                if rank > best.map(|(rank, _)| rank).unwrap_or(0) {
+                    best = Some((rank, subtable));
+                }
+            }
+        }
+
+        let mut coverage = Vec::new();
+        if let Some((_, subtable)) = best {
+            match subtable.u16(0)? {
+                4 => parse_format_4(subtable, &mut coverage)?,
+                12 => parse_format_12(subtable, &mut coverage)?,
+                _ => {}
+            }
+        }
+
+        Some(Table {
+            encodings,
+            coverage: merge(coverage),
+        })
+    }
+
+    pub fn contains(&self, c: Unichar) -> bool {
+        self.coverage
+            .binary_search_by(|range| {
+                if *range.end() < c {
+                    std::cmp::Ordering::Less
+                } else if *range.start() > c {
+                    std::cmp::Ordering::Greater
+                } else {
+                    std::cmp::Ordering::Equal
+                }
+            })
+            .is_ok()
+    }
+
+    /// The number of characters that are mapped to a glyph.
+    pub fn count(&self) -> usize {
+        self.coverage
+            .iter()
+            .map(|range| (range.end() - range.start()) as usize + 1)
+            .sum()
+    }
+}
+
+/// Segment mapping to delta values.
+fn parse_format_4(subtable: Reader, coverage: &mut Vec<RangeInclusive<Unichar>>) -> Option<()> {
+    let seg_count = usize::from(subtable.u16(6)? / 2);
+    let end_codes = 14;
+    let start_codes = end_codes + seg_count * 2 + 2;
+    let id_deltas = start_codes + seg_count * 2;
+    let id_range_offsets = id_deltas + seg_count * 2;
+
+    for segment in 0..seg_count {
+        let end = subtable.u16(end_codes + segment * 2)?;
+        let start = subtable.u16(start_codes + segment * 2)?;
+        let delta = subtable.u16(id_deltas + segment * 2)?;
+        let range_offset_position = id_range_offsets + segment * 2;
+        let range_offset = usize::from(subtable.u16(range_offset_position)?);
+        if start > end || start == 0xffff {
+            continue;
+        }
+
+        for c in start..=end {
+            let glyph = if range_offset == 0 {
+                c.wrapping_add(delta)
+            } else {
+                let position = range_offset_position + range_offset + usize::from(c - start) * 2;
+                match subtable.u16(position)? {
+                    0 => 0,
+                    glyph => glyph.wrapping_add(delta),
+                }
+            };
+            if glyph != 0 {
+                push(coverage, Unichar::from(c));
+            }
+        }
+    }
+    Some(())
+}
+
+/// Segmented coverage.
+fn parse_format_12(subtable: Reader, coverage: &mut Vec<RangeInclusive<Unichar>>) -> Option<()> {
+    let count = subtable.u32(12)? as usize;
+    for group in 0..count {
+        let group = subtable.at(16 + group * 12)?;
+        let start = group.u32(0)?;
+        let end = group.u32(4)?.min(0x10_ffff);
+        let start_glyph = group.u32(8)?;
+        // a group that starts at glyph 0 maps its first character to the missing glyph.
+        let start = if start_glyph == 0 {
+            start.checked_add(1)?
+        } else {
+            start
+        };
+        if start <= end {
+            coverage.push(start as Unichar..=end as Unichar);
+        }
+    }
+    Some(())
+}
+
+fn push(coverage: &mut Vec<RangeInclusive<Unichar>>, c: Unichar) {
+    match coverage.last_mut() {
+        Some(last) if *last.end() + 1 == c => *last = *last.start()..=c,
+        _ => coverage.push(c..=c),
+    }
+}
+
+fn merge(mut ranges: Vec<RangeInclusive<Unichar>>) -> Vec<RangeInclusive<Unichar>> {
+    ranges.sort_by_key(|range| *range.start());
+    let mut merged: Vec<RangeInclusive<Unichar>> = Vec::with_capacity(ranges.len());
+    for range in ranges {
+        match merged.last_mut() {
+            Some(last) if *range.start() <= *last.end() + 1 => {
+                if range.end() > last.end() {
+                    *last = *last.start()..=*range.end();
+                }
+            }
+            _ => merged.push(range),
+        }
+    }
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Table;
+    use crate::{Typeface, Unichar};
+
+    #[test]
+    fn parse_format_4_subtable() {
+        #[rustfmt::skip]
+        let data = [
+            0, 0, 0, 1,
+            0, 3, 0, 1, 0, 0, 0, 12,
+            // format 4, length, language, segCountX2 = 6, searchRange, entrySelector, rangeShift
+            0, 4, 0, 48, 0, 0, 0, 6, 0, 4, 0, 1, 0, 2,
+            // end codes
+            0, b'C', 0, b'z', 0xff, 0xff,
+            0, 0,
+            // start codes
+            0, b'A', 0, b'x', 0xff, 0xff,
+            // id deltas: 'A' -> 1, 'x'.. via range offsets, 0xffff -> 0
+            0xff, 0xc0, 0, 0, 0, 1,
+            // id range offsets
+            0, 0, 0, 4, 0, 0,
+            // glyph ids of 'x', 'y', 'z'
+            0, 10, 0, 0, 0, 11,
+        ];
+        let table = Table::parse(&data).unwrap();
+        assert_eq!(table.encodings.len(), 1);
+        assert_eq!(table.encodings[0].format, 4);
+        assert_eq!(table.coverage, vec![65..=67, 120..=120, 122..=122]);
+        assert!(table.contains('B' as Unichar));
+        assert!(!table.contains('y' as Unichar));
+        assert_eq!(table.count(), 5);
+    }
+
+    #[test]
+    fn parse_format_12_subtable() {
+        #[rustfmt::skip]
+        let data = [
+            0, 0, 0, 1,
+            0, 3, 0, 10, 0, 0, 0, 12,
+            // format 12, reserved, length, language, numGroups
+            0, 12, 0, 0, 0, 0, 0, 40, 0, 0, 0, 0, 0, 0, 0, 2,
+            0, 0, 0, 0x20, 0, 0, 0, 0x7e, 0, 0, 0, 1,
+            0, 1, 0xf6, 0, 0, 1, 0xf6, 0x4f, 0, 0, 1, 0,
+        ];
+        let table = Table::parse(&data).unwrap();
+        assert_eq!(table.coverage, vec![0x20..=0x7e, 0x1f600..=0x1f64f]);
+        assert!(table.contains(0x1f600));
+    }
+
+    #[test]
+    fn parse_malformed_format_12_group() {
+        #[rustfmt::skip]
+        let data = [
+            0, 0, 0, 1,
+            0, 3, 0, 10, 0, 0, 0, 12,
+            0, 12, 0, 0, 0, 0, 0, 28, 0, 0, 0, 0, 0, 0, 0, 1,
+            // the group starts at the last code point with the missing glyph.
+            0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0, 0, 0, 0,
+        ];
+        assert!(Table::parse(&data).is_none());
+    }
+
+    #[test]
+    fn default_typeface_covers_latin_letters() {
+        let table = Table::from_typeface(&Typeface::default()).unwrap();
+        assert!(table.contains('a' as Unichar));
+    }
+}
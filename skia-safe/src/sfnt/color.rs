@@ -0,0 +1,340 @@
+//! The glyphs of the color tables `COLR`, `CBLC` / `CBDT` and `sbix`.
+//!
+//! Only the glyph coverage is parsed, the color layers and bitmaps are left to Skia.
+
+use super::reader::Reader;
+use crate::prelude::*;
+use crate::typeface::FontId;
+use crate::{FourByteTag, GlyphId, Typeface};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+pub const COLR_TAG: FourByteTag = FourByteTag::from_chars('C', 'O', 'L', 'R');
+pub const CBLC_TAG: FourByteTag = FourByteTag::from_chars('C', 'B', 'L', 'C');
+pub const SBIX_TAG: FourByteTag = FourByteTag::from_chars('s', 'b', 'i', 'x');
+
+/// The set of glyphs a typeface draws in their own colors.
+///
+/// A glyph is a color glyph if it has layers in the `COLR` table, or a bitmap in one of the
+/// strikes of the `CBLC` or `sbix` tables, independently of the colors it's drawn in.
+#[derive(Clone, PartialEq, Eq, Default, Debug)]
+pub struct ColorGlyphs {
+    /// Sorted and deduplicated.
+    glyphs: Vec<GlyphId>,
+}
+
+impl ColorGlyphs {
+    pub fn from_typeface(typeface: &Typeface) -> ColorGlyphs {
+        let colr = typeface.copy_table_data(*COLR_TAG);
+        let cblc = typeface.copy_table_data(*CBLC_TAG);
+        // sbix tables contain the bitmaps, so only the offset arrays are read.
+        let sbix = typeface.get_table_size(*SBIX_TAG).and_then(|_| {
+            let glyph_count = typeface.count_glyphs().max(0) as usize;
+            parse_sbix(
+                |offset, len| read_table_data(typeface, SBIX_TAG, offset, len),
+                glyph_count,
+            )
+        });
+        Self::from_tables(
+            colr.as_ref().map(Vec::as_slice),
+            cblc.as_ref().map(Vec::as_slice),
+            sbix,
+        )
+    }
+
+    /// The color glyphs of the typeface, parsed once per typeface.
+    pub(crate) fn cached(typeface: &Typeface) -> Arc<ColorGlyphs> {
+        lazy_static! {
+            static ref CACHE: Mutex<HashMap<FontId, Arc<ColorGlyphs>>> = Default::default();
+        }
+        let id = typeface.unique_id();
+        if let Some(glyphs) = CACHE.lock().unwrap().get(&id) {
+            return glyphs.clone();
+        }
+        // parsed without holding the lock, a concurrent call may parse the tables, too.
+        let glyphs = Arc::new(Self::from_typeface(typeface));
+        CACHE.lock().unwrap().entry(id).or_insert(glyphs).clone()
+    }
+
+    /// Parses the color glyphs from the raw data of the tables the typeface contains.
+    pub fn parse(
+        colr: Option<&[u8]>,
+        cblc: Option<&[u8]>,
+        sbix: Option<&[u8]>,
+        glyph_count: usize,
+    ) -> ColorGlyphs {
+        let sbix = sbix.and_then(|sbix| {
+            parse_sbix(
+                |offset, len| Some(Reader::new(sbix).bytes(offset, len)?.to_vec()),
+                glyph_count,
+            )
+        });
+        Self::from_tables(colr, cblc, sbix)
+    }
+
+    fn from_tables(
+        colr: Option<&[u8]>,
+        cblc: Option<&[u8]>,
+        sbix: Option<Vec<GlyphId>>,
+    ) -> ColorGlyphs {
+        // tables that can not be parsed do not contribute any glyphs.
+        let mut glyphs: Vec<GlyphId> = colr.and_then(parse_colr).unwrap_or_default();
+        glyphs.extend(cblc.and_then(parse_cblc).unwrap_or_default());
+        glyphs.extend(sbix.unwrap_or_default());
+        glyphs.sort_unstable();
+        glyphs.dedup();
+        ColorGlyphs { glyphs }
+    }
+
+    pub fn contains(&self, glyph: GlyphId) -> bool {
+        self.glyphs.binary_search(&glyph).is_ok()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.glyphs.is_empty()
+    }
+
+    /// The color glyphs in ascending order.
+    pub fn glyphs(&self) -> &[GlyphId] {
+        &self.glyphs
+    }
+}
+
+fn read_table_data(
+    typeface: &Typeface,
+    tag: FourByteTag,
+    offset: usize,
+    len: usize,
+) -> Option<Vec<u8>> {
+    // the lengths are read from the table, so they are checked before allocating.
+    let table_size = typeface.get_table_size(*tag)?;
+    if len > table_size.checked_sub(offset)? {
+        return None;
+    }
+    let mut data = vec![0u8; len];
+    let read = unsafe {
+        typeface
+            .native()
+            .getTableData(*tag, offset, len, data.as_mut_ptr() as _)
+    };
+    if read == len {
+        Some(data)
+    } else {
+        None
+    }
+}
+
+/// The glyphs of the base glyph records of a `COLR` table.
+fn parse_colr(data: &[u8]) -> Option<Vec<GlyphId>> {
+    let data = Reader::new(data);
+    let count = usize::from(data.u16(2)?);
+    let records = data.at(data.u32(4)? as usize)?;
+    let mut glyphs = Vec::new();
+    for i in 0..count {
+        if records.u16(i * 6 + 4)? != 0 {
+            glyphs.push(records.u16(i * 6)?)
+        }
+    }
+    Some(glyphs)
+}
+
+/// The glyphs that have a bitmap in one of the strikes of a `CBLC` table.
+fn parse_cblc(data: &[u8]) -> Option<Vec<GlyphId>> {
+    let data = Reader::new(data);
+    let mut glyphs = Vec::new();
+    for size in 0..data.u32(4)? as usize {
+        let size = data.at(8 + size * 48)?;
+        let array = data.at(size.u32(0)? as usize)?;
+        for i in 0..size.u32(8)? as usize {
+            let first = array.u16(i * 8)?;
+            let last = array.u16(i * 8 + 2)?;
+            let subtable = array.at(array.u32(i * 8 + 4)? as usize)?;
+            parse_index_subtable(subtable, first, last, &mut glyphs)?;
+        }
+    }
+    Some(glyphs)
+}
+
+fn parse_index_subtable(
+    subtable: Reader,
+    first: GlyphId,
+    last: GlyphId,
+    glyphs: &mut Vec<GlyphId>,
+) -> Option<()> {
+    if last < first {
+        return None;
+    }
+    let range = first..=last;
+    match subtable.u16(0)? {
+        // offset arrays, a glyph without data has the same offset as its successor.
+        1 => {
+            for (i, glyph) in range.enumerate() {
+                if subtable.u32(8 + i * 4 + 4)? > subtable.u32(8 + i * 4)? {
+                    glyphs.push(glyph)
+                }
+            }
+        }
+        3 => {
+            for (i, glyph) in range.enumerate() {
+                if subtable.u16(8 + i * 2 + 2)? > subtable.u16(8 + i * 2)? {
+                    glyphs.push(glyph)
+                }
+            }
+        }
+        // all glyphs of the range have a bitmap of the same size.
+        2 => glyphs.extend(range),
+        // sparse glyph arrays.
+        4 => {
+            let count = subtable.u32(8)? as usize;
+            for i in 0..count {
+                glyphs.push(subtable.u16(12 + i * 4)?)
+            }
+        }
+        5 => {
+            let count = subtable.u32(20)? as usize;
+            for i in 0..count {
+                glyphs.push(subtable.u16(24 + i * 2)?)
+            }
+        }
+        _ => {}
+    }
+    Some(())
+}
+
+/// The glyphs that have data in one of the strikes of a `sbix` table.
+fn parse_sbix(
+    read: impl Fn(usize, usize) -> Option<Vec<u8>>,
+    glyph_count: usize,
+) -> Option<Vec<GlyphId>> {
+    let header = read(0, 8)?;
+    let strike_count = Reader::new(&header).u32(4)? as usize;
+    let strikes = read(8, strike_count.checked_mul(4)?)?;
+    let strikes = Reader::new(&strikes);
+
+    let mut has_data = vec![false; glyph_count];
+    for strike in 0..strike_count {
+        let strike = strikes.u32(strike * 4)? as usize;
+        let offsets = read(strike.checked_add(4)?, (glyph_count + 1) * 4)?;
+        let offsets = Reader::new(&offsets);
+        for (glyph, has_data) in has_data.iter_mut().enumerate() {
+            if offsets.u32(glyph * 4 + 4)? > offsets.u32(glyph * 4)? {
+                *has_data = true
+            }
+        }
+    }
+    Some(
+        has_data
+            .iter()
+            .enumerate()
+            .filter(|(_, has_data)| **has_data)
+            .map(|(glyph, _)| glyph as GlyphId)
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ColorGlyphs;
+
+    #[test]
+    fn parse_colr_base_glyphs() {
+        #[rustfmt::skip]
+        let colr = [
+            0, 0, 0, 3,
+            0, 0, 0, 14,
+            0, 0, 0, 32,
+            0, 2,
+            // glyph, first layer, layer count
+            0, 4, 0, 0, 0, 2,
+            0, 7, 0, 2, 0, 0,
+            0, 9, 0, 2, 0, 1,
+        ];
+        let glyphs = ColorGlyphs::parse(Some(&colr), None, None, 10);
+        assert_eq!(glyphs.glyphs(), &[4, 9]);
+        assert!(ColorGlyphs::parse(Some(&colr[..30]), None, None, 10).is_empty());
+    }
+
+    #[test]
+    fn parse_cblc_index_subtables() {
+        #[rustfmt::skip]
+        let header = [
+            0, 3, 0, 0,
+            0, 0, 0, 1,
+            // bitmap size
+            0, 0, 0, 56,
+            0, 0, 0, 0,
+            0, 0, 0, 3,
+            0, 0, 0, 0,
+        ];
+        #[rustfmt::skip]
+        let index = [
+            // index subtable array
+            0, 1, 0, 3, 0, 0, 0, 24,
+            0, 5, 0, 6, 0, 0, 0, 48,
+            0, 8, 0, 20, 0, 0, 0, 56,
+            // format 1: glyph 2 has no data
+            0, 1, 0, 17, 0, 0, 0, 0,
+            0, 0, 0, 0, 0, 0, 0, 10, 0, 0, 0, 10, 0, 0, 0, 20,
+            // format 2
+            0, 2, 0, 17, 0, 0, 0, 20,
+            // format 4
+            0, 4, 0, 17, 0, 0, 0, 30,
+            0, 0, 0, 2,
+            0, 8, 0, 0,
+            0, 20, 0, 10,
+            0, 0, 0, 20,
+        ];
+        let cblc = [&header[..], &[0; 32], &index].concat();
+        let glyphs = ColorGlyphs::parse(None, Some(&cblc), None, 30);
+        assert_eq!(glyphs.glyphs(), &[1, 3, 5, 6, 8, 20]);
+    }
+
+    #[test]
+    fn parse_sbix_strikes() {
+        #[rustfmt::skip]
+        let sbix = [
+            0, 1, 0, 1,
+            0, 0, 0, 2,
+            0, 0, 0, 16,
+            0, 0, 0, 36,
+            // strike 1: glyph 1 has data
+            0, 20, 0, 72,
+            0, 0, 0, 20, 0, 0, 0, 20, 0, 0, 0, 30, 0, 0, 0, 30,
+            // strike 2: glyphs 1 and 2 have data
+            0, 40, 0, 72,
+            0, 0, 0, 20, 0, 0, 0, 20, 0, 0, 0, 30, 0, 0, 0, 40,
+        ];
+        let glyphs = ColorGlyphs::parse(None, None, Some(&sbix), 3);
+        assert_eq!(glyphs.glyphs(), &[1, 2]);
+        assert!(glyphs.contains(2));
+        assert!(!glyphs.contains(0));
+        assert!(ColorGlyphs::parse(None, None, Some(&sbix), 4).is_empty());
+    }
+
+    #[test]
+    fn parse_truncated_sbix() {
+        #[rustfmt::skip]
+        let sbix = [
+            0, 1, 0, 1,
+            // strike count
+            0xff, 0xff, 0xff, 0xff,
+            0, 0, 0, 16,
+        ];
+        assert!(ColorGlyphs::parse(None, None, Some(&sbix), 3).is_empty());
+    }
+
+    #[test]
+    fn table_data_is_not_read_beyond_the_table() {
+        use super::{read_table_data, CBLC_TAG, SBIX_TAG};
+        use crate::{Data, Typeface};
+
+        let data = Data::new_copy(include_bytes!("../../resources/fonts/color_glyphs.ttf"));
+        let typeface = Typeface::from_data(&data, None).unwrap();
+        let size = typeface.get_table_size(*CBLC_TAG).unwrap();
+        assert!(read_table_data(&typeface, CBLC_TAG, 0, size).is_some());
+        assert!(read_table_data(&typeface, CBLC_TAG, 4, size).is_none());
+        assert!(read_table_data(&typeface, CBLC_TAG, 4, 0xffff_fffc).is_none());
+        assert!(read_table_data(&typeface, CBLC_TAG, size + 1, 0).is_none());
+        assert!(read_table_data(&typeface, SBIX_TAG, 0, 8).is_none());
+    }
+}
@@ -0,0 +1,187 @@
+//! The font variations table `fvar`.
+
+use super::name;
+use super::reader::Reader;
+use crate::font_arguments::variation_position::Coordinate;
+use crate::font_parameters::VariationAxis;
+use crate::{FourByteTag, Typeface};
+
+pub const TAG: FourByteTag = FourByteTag::from_chars('f', 'v', 'a', 'r');
+
+/// A variation axis, like weight `wght` or width `wdth`, and its name.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Axis {
+    /// The tag, range and flags of the axis. Hidden axes should not be exposed in user
+    /// interfaces.
+    pub variation: VariationAxis,
+    pub name_id: u16,
+    /// The name resolved from the `name` table, `None` if the table was parsed without it.
+    pub name: Option<String>,
+}
+
+impl Axis {
+    /// Returns `value` clamped to the axis' range.
+    pub fn clamp(&self, value: f32) -> f32 {
+        value.max(self.variation.min).min(self.variation.max)
+    }
+}
+
+/// A named instance, like "SemiBold Condensed", is a predefined position in the design space.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Instance {
+    pub subfamily_name_id: u16,
+    pub subfamily_name: Option<String>,
+    pub postscript_name_id: Option<u16>,
+    pub postscript_name: Option<String>,
+    /// One coordinate for each of the table's axes.
+    pub coordinates: Vec<Coordinate>,
+}
+
+#[derive(Clone, PartialEq, Default, Debug)]
+pub struct Table {
+    pub axes: Vec<Axis>,
+    pub instances: Vec<Instance>,
+}
+
+impl Table {
+    /// Loads the `fvar` table of the typeface and resolves the names of its axes and instances.
+    pub fn from_typeface(typeface: &Typeface) -> Option<Table> {
+        let mut table = Self::parse(&typeface.copy_table_data(*TAG)?)?;
+        if let Some(names) = name::Table::from_typeface(typeface) {
+            table.resolve_names(&names);
+        }
+        Some(table)
+    }
+
+    pub fn parse(data: &[u8]) -> Option<Table> {
+        const HIDDEN_AXIS: u16 = 0x0001;
+
+        let data = Reader::new(data);
+        let axes_offset = usize::from(data.u16(4)?);
+        let axis_count = usize::from(data.u16(8)?);
+        let axis_size = usize::from(data.u16(10)?);
+        let instance_count = usize::from(data.u16(12)?);
+        let instance_size = usize::from(data.u16(14)?);
+        if axis_size < 20 || instance_size < axis_count * 4 + 4 {
+            return None;
+        }
+
+        let axes = (0..axis_count)
+            .map(|i| {
+                let axis = data.at(axes_offset + i * axis_size)?;
+                let mut variation = VariationAxis {
+                    tag: axis.tag(0)?,
+                    min: axis.fixed(4)?,
+                    def: axis.fixed(8)?,
+                    max: axis.fixed(12)?,
+                    ..VariationAxis::default()
+                };
+                variation.set_hidden(axis.u16(16)? & HIDDEN_AXIS != 0);
+                Some(Axis {
+                    variation,
+                    name_id: axis.u16(18)?,
+                    name: None,
+                })
+            })
+            .collect::<Option<Vec<_>>>()?;
+
+        let instances_offset = axes_offset + axis_count * axis_size;
+        let has_postscript_name = instance_size >= axis_count * 4 + 6;
+        let instances = (0..instance_count)
+            .map(|i| {
+                let instance = data.at(instances_offset + i * instance_size)?;
+                let coordinates = axes
+                    .iter()
+                    .enumerate()
+                    .map(|(i, axis)| {
+                        Some(Coordinate {
+                            axis: axis.variation.tag,
+                            value: instance.fixed(4 + i * 4)?,
+                        })
+                    })
+                    .collect::<Option<Vec<_>>>()?;
+                let postscript_name_id = if has_postscript_name {
+                    Some(instance.u16(4 + axis_count * 4)?)
+                } else {
+                    None
+                };
+                Some(Instance {
+                    subfamily_name_id: instance.u16(0)?,
+                    subfamily_name: None,
+                    postscript_name_id,
+                    postscript_name: None,
+                    coordinates,
+                })
+            })
+            .collect::<Option<Vec<_>>>()?;
+
+        Some(Table { axes, instances })
+    }
+
+    pub fn resolve_names(&mut self, names: &name::Table) {
+        let find = |id: u16| names.find(id).map(String::from);
+        for axis in &mut self.axes {
+            axis.name = find(axis.name_id);
+        }
+        for instance in &mut self.instances {
+            instance.subfamily_name = find(instance.subfamily_name_id);
+            // 0xffff marks a missing PostScript name.
+            instance.postscript_name = instance
+                .postscript_name_id
+                .filter(|id| *id != 0xffff)
+                .and_then(find);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Table;
+    use crate::FourByteTag;
+
+    /// A table with the axes `wght` (100..900) and `wdth` (75..100, hidden), and the instances
+    /// "Bold" and "SemiBold Condensed".
+    #[rustfmt::skip]
+    const FVAR: [u8; 84] = [
+        0, 1, 0, 0, 0, 16, 0, 2, 0, 2, 0, 20, 0, 2, 0, 14,
+        b'w', b'g', b'h', b't', 0, 100, 0, 0, 1, 144, 0, 0, 3, 132, 0, 0, 0, 0, 1, 0,
+        b'w', b'd', b't', b'h', 0, 75, 0, 0, 0, 100, 0, 0, 0, 100, 0, 0, 0, 1, 1, 1,
+        1, 2, 0, 0, 2, 188, 0, 0, 0, 100, 0, 0, 0xff, 0xff,
+        1, 3, 0, 0, 2, 88, 0, 0, 0, 75, 0, 0, 1, 4,
+    ];
+
+    #[test]
+    #[allow(clippy::float_cmp)]
+    fn parse_fvar_table() {
+        let table = Table::parse(&FVAR).unwrap();
+
+        assert_eq!(table.axes.len(), 2);
+        let weight = &table.axes[0];
+        assert_eq!(
+            weight.variation.tag,
+            FourByteTag::from_chars('w', 'g', 'h', 't')
+        );
+        let range = (
+            weight.variation.min,
+            weight.variation.def,
+            weight.variation.max,
+        );
+        assert_eq!(range, (100.0, 400.0, 900.0));
+        assert!(!weight.variation.is_hidden());
+        assert_eq!(weight.name_id, 256);
+        assert!(table.axes[1].variation.is_hidden());
+
+        assert_eq!(table.instances.len(), 2);
+        assert_eq!(table.instances[0].coordinates[0].value, 700.0);
+        let semi_bold_condensed = &table.instances[1];
+        assert_eq!(semi_bold_condensed.subfamily_name_id, 259);
+        assert_eq!(semi_bold_condensed.postscript_name_id, Some(260));
+        assert_eq!(semi_bold_condensed.coordinates[0].value, 600.0);
+        assert_eq!(semi_bold_condensed.coordinates[1].value, 75.0);
+    }
+
+    #[test]
+    fn parse_truncated_fvar_table() {
+        assert!(Table::parse(&FVAR[..80]).is_none());
+    }
+}
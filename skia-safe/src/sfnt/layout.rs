@@ -0,0 +1,160 @@
+//! The script and feature lists of the glyph substitution table `GSUB` and the glyph
+//! positioning table `GPOS`.
+
+use super::reader::Reader;
+use crate::{FourByteTag, Typeface};
+
+pub const GSUB: FourByteTag = FourByteTag::from_chars('G', 'S', 'U', 'B');
+pub const GPOS: FourByteTag = FourByteTag::from_chars('G', 'P', 'O', 'S');
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Script {
+    /// The script tag, like `latn` or `arab`.
+    pub tag: FourByteTag,
+    pub languages: Vec<Language>,
+}
+
+/// The features of a language system.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Language {
+    /// The language tag, like `DEU `, or `None` for the script's default language system.
+    pub tag: Option<FourByteTag>,
+    /// The index of the feature that is required for this language system.
+    pub required_feature: Option<usize>,
+    /// Indices into the table's `features`.
+    pub features: Vec<usize>,
+}
+
+#[derive(Clone, PartialEq, Eq, Default, Debug)]
+pub struct Table {
+    pub scripts: Vec<Script>,
+    /// The feature tags, like `liga` or `kern`. A tag may appear more than once if the font
+    /// defines different features for different language systems.
+    pub features: Vec<FourByteTag>,
+}
+
+impl Table {
+    /// Loads the table `tag`, which is either `GSUB` or `GPOS`.
+    pub fn from_typeface(typeface: &Typeface, tag: FourByteTag) -> Option<Table> {
+        Self::parse(&typeface.copy_table_data(*tag)?)
+    }
+
+    pub fn parse(data: &[u8]) -> Option<Table> {
+        let data = Reader::new(data);
+        let script_list = data.at(usize::from(data.u16(4)?))?;
+        let feature_list = data.at(usize::from(data.u16(6)?))?;
+
+        let scripts = records(script_list)?
+            .into_iter()
+            .map(|(tag, offset)| {
+                let script = script_list.at(offset)?;
+                let mut languages = Vec::new();
+                let default_offset = usize::from(script.u16(0)?);
+                if default_offset != 0 {
+                    languages.push(language(script.at(default_offset)?, None)?);
+                }
+                for (tag, offset) in records(script.at(2)?)? {
+                    languages.push(language(script.at(offset)?, Some(tag))?);
+                }
+                Some(Script { tag, languages })
+            })
+            .collect::<Option<Vec<_>>>()?;
+
+        let features = records(feature_list)?
+            .into_iter()
+            .map(|(tag, _)| tag)
+            .collect();
+
+        Some(Table { scripts, features })
+    }
+
+    /// Returns the sorted and deduplicated feature tags of all language systems.
+    pub fn feature_tags(&self) -> Vec<FourByteTag> {
+        let mut tags = self.features.clone();
+        tags.sort_by_key(|tag| **tag);
+        tags.dedup();
+        tags
+    }
+}
+
+/// Reads a list of tagged records that consist of a count and (tag, offset) pairs.
+fn records(list: Reader) -> Option<Vec<(FourByteTag, usize)>> {
+    (0..usize::from(list.u16(0)?))
+        .map(|i| Some((list.tag(2 + i * 6)?, usize::from(list.u16(6 + i * 6)?))))
+        .collect()
+}
+
+fn language(lang_sys: Reader, tag: Option<FourByteTag>) -> Option<Language> {
+    let required_feature = match lang_sys.u16(2)? {
+        0xffff => None,
+        index => Some(usize::from(index)),
+    };
+    let features = (0..usize::from(lang_sys.u16(4)?))
+        .map(|i| lang_sys.u16(6 + i * 2).map(usize::from))
+        .collect::<Option<Vec<_>>>()?;
+    Some(Language {
+        tag,
+        required_feature,
+        features,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Table, GSUB};
+    use crate::{FourByteTag, Typeface};
+
+    #[test]
+    fn parse_script_and_feature_lists() {
+        #[rustfmt::skip]
+        let data = [
+            // version 1.0, script list, feature list, lookup list
+            0, 1, 0, 0, 0, 10, 0, 46, 0, 0,
+            // script list: 1 script
+            0, 1, b'l', b'a', b't', b'n', 0, 8,
+            // script: default language system at 18, 1 language system
+            0, 18, 0, 1, b'D', b'E', b'U', b' ', 0, 10,
+            // DEU: required feature 1, 1 feature
+            0, 0, 0, 1, 0, 1, 0, 0,
+            // default: no required feature, 2 features
+            0, 0, 0xff, 0xff, 0, 2, 0, 0, 0, 1,
+            // feature list: 3 features
+            0, 3,
+            b'l', b'i', b'g', b'a', 0, 0,
+            b'k', b'e', b'r', b'n', 0, 0,
+            b'l', b'i', b'g', b'a', 0, 0,
+        ];
+        let table = Table::parse(&data).unwrap();
+        assert_eq!(table.scripts.len(), 1);
+        let latin = &table.scripts[0];
+        assert_eq!(latin.tag, FourByteTag::from_chars('l', 'a', 't', 'n'));
+        assert_eq!(latin.languages.len(), 2);
+        assert_eq!(latin.languages[0].tag, None);
+        assert_eq!(latin.languages[0].required_feature, None);
+        assert_eq!(latin.languages[0].features, vec![0, 1]);
+        assert_eq!(
+            latin.languages[1].tag,
+            Some(FourByteTag::from_chars('D', 'E', 'U', ' '))
+        );
+        assert_eq!(latin.languages[1].required_feature, Some(1));
+        assert_eq!(
+            table.feature_tags(),
+            vec![
+                FourByteTag::from_chars('k', 'e', 'r', 'n'),
+                FourByteTag::from_chars('l', 'i', 'g', 'a')
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_gsub_of_default_typeface() {
+        if let Some(table) = Table::from_typeface(&Typeface::default(), GSUB) {
+            assert!(table
+                .scripts
+                .iter()
+                .flat_map(|script| &script.languages)
+                .flat_map(|language| &language.features)
+                .all(|index| *index < table.features.len()));
+        }
+    }
+}
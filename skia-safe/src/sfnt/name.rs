@@ -0,0 +1,162 @@
+//! The naming table `name`.
+
+use super::reader::Reader;
+use crate::{FourByteTag, Typeface};
+
+pub const TAG: FourByteTag = FourByteTag::from_chars('n', 'a', 'm', 'e');
+
+/// Predefined name ids.
+pub mod id {
+    pub const COPYRIGHT: u16 = 0;
+    pub const FONT_FAMILY: u16 = 1;
+    pub const FONT_SUBFAMILY: u16 = 2;
+    pub const UNIQUE_ID: u16 = 3;
+    pub const FULL_NAME: u16 = 4;
+    pub const VERSION: u16 = 5;
+    pub const POSTSCRIPT_NAME: u16 = 6;
+    pub const TRADEMARK: u16 = 7;
+    pub const MANUFACTURER: u16 = 8;
+    pub const DESIGNER: u16 = 9;
+    pub const DESCRIPTION: u16 = 10;
+    pub const VENDOR_URL: u16 = 11;
+    pub const DESIGNER_URL: u16 = 12;
+    pub const LICENSE: u16 = 13;
+    pub const LICENSE_URL: u16 = 14;
+    pub const TYPOGRAPHIC_FAMILY: u16 = 16;
+    pub const TYPOGRAPHIC_SUBFAMILY: u16 = 17;
+    pub const SAMPLE_TEXT: u16 = 19;
+    pub const VARIATIONS_POSTSCRIPT_NAME_PREFIX: u16 = 25;
+}
+
+pub mod platform_id {
+    pub const UNICODE: u16 = 0;
+    pub const MACINTOSH: u16 = 1;
+    pub const WINDOWS: u16 = 3;
+}
+
+/// The Windows language id of US English.
+pub const WINDOWS_ENGLISH_US: u16 = 0x0409;
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Record {
+    pub platform_id: u16,
+    pub encoding_id: u16,
+    pub language_id: u16,
+    pub name_id: u16,
+    /// The decoded string, `None` if the encoding is not supported.
+    pub string: Option<String>,
+}
+
+#[derive(Clone, PartialEq, Eq, Default, Debug)]
+pub struct Table {
+    pub records: Vec<Record>,
+}
+
+impl Table {
+    pub fn from_typeface(typeface: &Typeface) -> Option<Table> {
+        Self::parse(&typeface.copy_table_data(*TAG)?)
+    }
+
+    pub fn parse(data: &[u8]) -> Option<Table> {
+        let data = Reader::new(data);
+        let count = data.u16(2)?;
+        let strings = usize::from(data.u16(4)?);
+
+        let records = (0..usize::from(count))
+            .map(|i| {
+                let record = data.at(6 + i * 12)?;
+                let platform_id = record.u16(0)?;
+                let encoding_id = record.u16(2)?;
+                let bytes = data.bytes(
+                    strings + usize::from(record.u16(10)?),
+                    usize::from(record.u16(8)?),
+                )?;
+                Some(Record {
+                    platform_id,
+                    encoding_id,
+                    language_id: record.u16(4)?,
+                    name_id: record.u16(6)?,
+                    string: decode(platform_id, encoding_id, bytes),
+                })
+            })
+            .collect::<Option<Vec<_>>>()?;
+
+        Some(Table { records })
+    }
+
+    /// Returns the string for `name_id`, preferring US English Windows names, then
+    /// any other Windows or Unicode names and then Macintosh names.
+    pub fn find(&self, name_id: u16) -> Option<&str> {
+        let rank = |record: &Record| match (record.platform_id, record.language_id) {
+            (platform_id::WINDOWS, WINDOWS_ENGLISH_US) => 0,
+            (platform_id::WINDOWS, _) | (platform_id::UNICODE, _) => 1,
+            _ => 2,
+        };
+        self.records
+            .iter()
+            .filter(|record| record.name_id == name_id && record.string.is_some())
+            .min_by_key(|record| rank(record))
+            .and_then(|record| record.string.as_ref().map(String::as_str))
+    }
+}
+
+fn decode(platform_id: u16, encoding_id: u16, bytes: &[u8]) -> Option<String> {
+    match (platform_id, encoding_id) {
+        (platform_id::UNICODE, _)
+        | (platform_id::WINDOWS, 0)
+        | (platform_id::WINDOWS, 1)
+        | (platform_id::WINDOWS, 10) => {
+            let utf16: Vec<u16> = bytes
+                .chunks(2)
+                .filter(|c| c.len() == 2)
+                .map(|c| u16::from(c[0]) << 8 | u16::from(c[1]))
+                .collect();
+            String::from_utf16(&utf16).ok()
+        }
+        // Mac Roman, only its ASCII subset is decoded.
+        (platform_id::MACINTOSH, 0) => Some(
+            bytes
+                .iter()
+                .map(|&b| if b < 0x80 { b as char } else { '\u{fffd}' })
+                .collect(),
+        ),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{id, platform_id, Table};
+    use crate::Typeface;
+
+    #[test]
+    fn parse_name_table() {
+        #[rustfmt::skip]
+        let data = [
+            0, 0, 0, 2, 0, 30,
+            // Macintosh, Roman, English, family name, 3 bytes at 0
+            0, 1, 0, 0, 0, 0, 0, 1, 0, 3, 0, 0,
+            // Windows, Unicode BMP, en-US, family name, 6 bytes at 3
+            0, 3, 0, 1, 0x04, 0x09, 0, 1, 0, 6, 0, 3,
+            b'M', b'a', b'c',
+            0, b'W', 0, b'i', 0, b'n',
+        ];
+        let table = Table::parse(&data).unwrap();
+        assert_eq!(table.records.len(), 2);
+        assert_eq!(table.records[0].platform_id, platform_id::MACINTOSH);
+        assert_eq!(table.records[0].string.as_ref().unwrap(), "Mac");
+        assert_eq!(table.find(id::FONT_FAMILY), Some("Win"));
+        assert_eq!(table.find(id::FULL_NAME), None);
+    }
+
+    #[test]
+    fn parse_truncated_name_table() {
+        assert!(Table::parse(&[0, 0, 0, 1, 0, 18]).is_none());
+    }
+
+    #[test]
+    fn default_typeface_has_a_family_name() {
+        let table = Table::from_typeface(&Typeface::default()).unwrap();
+        assert!(table.find(id::FONT_FAMILY).is_some());
+    }
+}
@@ -0,0 +1,185 @@
+//! The OS/2 and Windows metrics table `OS/2`.
+
+use super::reader::Reader;
+use crate::{FourByteTag, Typeface};
+
+pub const TAG: FourByteTag = FourByteTag::from_chars('O', 'S', '/', '2');
+
+/// The usage permissions of the embedding licensing rights (`fsType`).
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum EmbeddingPermission {
+    /// The font may be embedded and permanently installed.
+    Installable,
+    /// The font must not be embedded without the legal owner's permission.
+    RestrictedLicense,
+    /// The font may be embedded in documents that are opened read-only.
+    PreviewAndPrint,
+    /// The font may be embedded in documents that can be edited.
+    Editable,
+}
+
+bitflags! {
+    pub struct EmbeddingFlags: u16 {
+        const NO_SUBSETTING = 0x0100;
+        const BITMAP_EMBEDDING_ONLY = 0x0200;
+    }
+}
+
+bitflags! {
+    /// The font selection flags (`fsSelection`).
+    pub struct Selection: u16 {
+        const ITALIC = 0x0001;
+        const UNDERSCORE = 0x0002;
+        const NEGATIVE = 0x0004;
+        const OUTLINED = 0x0008;
+        const STRIKEOUT = 0x0010;
+        const BOLD = 0x0020;
+        const REGULAR = 0x0040;
+        const USE_TYPO_METRICS = 0x0080;
+        const WWS = 0x0100;
+        const OBLIQUE = 0x0200;
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct Table {
+    pub version: u16,
+    pub avg_char_width: i16,
+    /// 100 (thin) to 900 (black).
+    pub weight_class: u16,
+    /// 1 (ultra-condensed) to 9 (ultra-expanded).
+    pub width_class: u16,
+    /// The raw embedding licensing rights, see `embedding_permission()` and
+    /// `embedding_flags()`.
+    pub fs_type: u16,
+    pub strikeout_size: i16,
+    pub strikeout_position: i16,
+    pub family_class: i16,
+    pub panose: [u8; 10],
+    pub vendor_id: FourByteTag,
+    pub selection: Selection,
+    pub first_char_index: u16,
+    pub last_char_index: u16,
+    pub typo_ascender: i16,
+    pub typo_descender: i16,
+    pub typo_line_gap: i16,
+    pub win_ascent: u16,
+    pub win_descent: u16,
+    /// Available since version 2.
+    pub x_height: Option<i16>,
+    /// Available since version 2.
+    pub cap_height: Option<i16>,
+}
+
+impl Table {
+    pub fn from_typeface(typeface: &Typeface) -> Option<Table> {
+        Self::parse(&typeface.copy_table_data(*TAG)?)
+    }
+
+    pub fn parse(data: &[u8]) -> Option<Table> {
+        let data = Reader::new(data);
+        let version = data.u16(0)?;
+        let mut panose = [0; 10];
+        panose.copy_from_slice(data.bytes(32, 10)?);
+        let (x_height, cap_height) = if version >= 2 {
+            (Some(data.i16(86)?), Some(data.i16(88)?))
+        } else {
+            (None, None)
+        };
+
+        Some(Table {
+            version,
+            avg_char_width: data.i16(2)?,
+            weight_class: data.u16(4)?,
+            width_class: data.u16(6)?,
+            fs_type: data.u16(8)?,
+            strikeout_size: data.i16(26)?,
+            strikeout_position: data.i16(28)?,
+            family_class: data.i16(30)?,
+            panose,
+            vendor_id: data.tag(58)?,
+            selection: Selection::from_bits_truncate(data.u16(62)?),
+            first_char_index: data.u16(64)?,
+            last_char_index: data.u16(66)?,
+            typo_ascender: data.i16(68)?,
+            typo_descender: data.i16(70)?,
+            typo_line_gap: data.i16(72)?,
+            win_ascent: data.u16(74)?,
+            win_descent: data.u16(76)?,
+            x_height,
+            cap_height,
+        })
+    }
+
+    /// The usage permission. Older fonts may set more than one permission, in which case the
+    /// least restrictive one applies.
+    pub fn embedding_permission(&self) -> EmbeddingPermission {
+        match self.fs_type & 0x000f {
+            0 => EmbeddingPermission::Installable,
+            bits if bits & 0x0008 != 0 => EmbeddingPermission::Editable,
+            bits if bits & 0x0004 != 0 => EmbeddingPermission::PreviewAndPrint,
+            _ => EmbeddingPermission::RestrictedLicense,
+        }
+    }
+
+    pub fn embedding_flags(&self) -> EmbeddingFlags {
+        EmbeddingFlags::from_bits_truncate(self.fs_type)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{EmbeddingFlags, EmbeddingPermission, Selection, Table};
+    use crate::{FourByteTag, Typeface};
+
+    fn version_2_table() -> Vec<u8> {
+        let mut data = vec![0u8; 96];
+        data[1] = 2; // version
+        data[4..6].copy_from_slice(&600u16.to_be_bytes()); // weight class
+        data[7] = 3; // width class
+        data[8..10].copy_from_slice(&0x0104u16.to_be_bytes()); // fs type
+        data[58..62].copy_from_slice(b"SKIA");
+        data[62..64].copy_from_slice(&0x00a1u16.to_be_bytes()); // selection
+        data[68..70].copy_from_slice(&800i16.to_be_bytes()); // typo ascender
+        data[70..72].copy_from_slice(&(-200i16).to_be_bytes()); // typo descender
+        data[88..90].copy_from_slice(&700i16.to_be_bytes()); // cap height
+        data
+    }
+
+    #[test]
+    fn parse_os2_table() {
+        let table = Table::parse(&version_2_table()).unwrap();
+        assert_eq!(table.weight_class, 600);
+        assert_eq!(table.width_class, 3);
+        assert_eq!(
+            table.embedding_permission(),
+            EmbeddingPermission::PreviewAndPrint
+        );
+        assert_eq!(table.embedding_flags(), EmbeddingFlags::NO_SUBSETTING);
+        assert_eq!(table.vendor_id, FourByteTag::from_chars('S', 'K', 'I', 'A'));
+        assert_eq!(
+            table.selection,
+            Selection::ITALIC | Selection::BOLD | Selection::USE_TYPO_METRICS
+        );
+        assert_eq!((table.typo_ascender, table.typo_descender), (800, -200));
+        assert_eq!(table.cap_height, Some(700));
+    }
+
+    #[test]
+    fn version_0_table_has_no_cap_height() {
+        let mut data = version_2_table();
+        data[1] = 0;
+        data.truncate(78);
+        let table = Table::parse(&data).unwrap();
+        assert_eq!(table.x_height, None);
+        assert_eq!(table.cap_height, None);
+        assert!(Table::parse(&data[..70]).is_none());
+    }
+
+    #[test]
+    fn default_typeface_has_a_weight_class() {
+        if let Some(table) = Table::from_typeface(&Typeface::default()) {
+            assert!(table.weight_class >= 1 && table.weight_class <= 1000);
+        }
+    }
+}
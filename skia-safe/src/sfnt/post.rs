@@ -0,0 +1,58 @@
+//! The PostScript table `post`.
+
+use super::reader::Reader;
+use crate::{FourByteTag, Typeface};
+
+pub const TAG: FourByteTag = FourByteTag::from_chars('p', 'o', 's', 't');
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct Table {
+    /// The raw version, for example `0x00020000` for version 2.0 and `0x00025000` for 2.5.
+    pub version: u32,
+    /// The italic angle in degrees counter-clockwise from the vertical.
+    pub italic_angle: f32,
+    pub underline_position: i16,
+    pub underline_thickness: i16,
+    pub is_fixed_pitch: bool,
+}
+
+impl Table {
+    pub fn from_typeface(typeface: &Typeface) -> Option<Table> {
+        Self::parse(&typeface.copy_table_data(*TAG)?)
+    }
+
+    pub fn parse(data: &[u8]) -> Option<Table> {
+        let data = Reader::new(data);
+        Some(Table {
+            version: data.u32(0)?,
+            italic_angle: data.fixed(4)?,
+            underline_position: data.i16(8)?,
+            underline_thickness: data.i16(10)?,
+            is_fixed_pitch: data.u32(12)? != 0,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Table;
+
+    #[test]
+    #[allow(clippy::float_cmp)]
+    fn parse_post_table() {
+        #[rustfmt::skip]
+        let data = [
+            0, 3, 0, 0,
+            0xff, 0xf4, 0x80, 0, // -11.5
+            0xff, 0x9c, 0, 50,
+            0, 0, 0, 1,
+        ];
+        let table = Table::parse(&data).unwrap();
+        assert_eq!(table.version, 0x0003_0000);
+        assert_eq!(table.italic_angle, -11.5);
+        assert_eq!(table.underline_position, -100);
+        assert_eq!(table.underline_thickness, 50);
+        assert!(table.is_fixed_pitch);
+        assert!(Table::parse(&data[..12]).is_none());
+    }
+}
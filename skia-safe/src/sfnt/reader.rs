@@ -0,0 +1,48 @@
+use crate::FourByteTag;
+
+/// A bounds checked reader for the big endian data of OpenType tables.
+#[derive(Copy, Clone)]
+pub(crate) struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    pub fn new(data: &'a [u8]) -> Reader<'a> {
+        Reader(data)
+    }
+
+    pub fn bytes(self, offset: usize, len: usize) -> Option<&'a [u8]> {
+        self.0.get(offset..offset.checked_add(len)?)
+    }
+
+    /// A reader for the data starting at `offset`.
+    pub fn at(self, offset: usize) -> Option<Reader<'a>> {
+        self.0.get(offset..).map(Reader)
+    }
+
+    pub fn u16(self, offset: usize) -> Option<u16> {
+        self.bytes(offset, 2)
+            .map(|b| u16::from(b[0]) << 8 | u16::from(b[1]))
+    }
+
+    pub fn i16(self, offset: usize) -> Option<i16> {
+        self.u16(offset).map(|v| v as i16)
+    }
+
+    pub fn u32(self, offset: usize) -> Option<u32> {
+        self.bytes(offset, 4).map(|b| {
+            u32::from(b[0]) << 24 | u32::from(b[1]) << 16 | u32::from(b[2]) << 8 | u32::from(b[3])
+        })
+    }
+
+    pub fn i32(self, offset: usize) -> Option<i32> {
+        self.u32(offset).map(|v| v as i32)
+    }
+
+    /// A 16.16 fixed point number.
+    pub fn fixed(self, offset: usize) -> Option<f32> {
+        self.i32(offset).map(|v| v as f32 / 65536.0)
+    }
+
+    pub fn tag(self, offset: usize) -> Option<FourByteTag> {
+        self.u32(offset).map(FourByteTag::from)
+    }
+}
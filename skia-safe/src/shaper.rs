@@ -0,0 +1,4 @@
+// TODO: bind the paragraph module (ParagraphBuilder, ParagraphStyle, TextStyle, placeholders)
+//       as soon as the Skia submodule is updated to a version that includes modules/skparagraph.
+mod _shaper;
+pub use self::_shaper::*;
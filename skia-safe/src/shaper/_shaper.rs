@@ -0,0 +1,209 @@
+use crate::prelude::*;
+use crate::{scalar, Font, GlyphId, Point, TextBlob, Vector};
+use skia_bindings::{
+    C_SkShaper_Make, C_SkShaper_MakePrimitive, C_SkShaper_delete, C_SkShaper_shape,
+    C_SkShaper_shapeRuns, SkFont, SkPoint, SkShaper,
+};
+use std::ffi::c_void;
+use std::ops::Range;
+use std::slice;
+
+/// Shapes text into positioned glyphs.
+pub struct Shaper(*mut SkShaper);
+
+impl NativeAccess<SkShaper> for Shaper {
+    fn native(&self) -> &SkShaper {
+        unsafe { &*self.0 }
+    }
+
+    fn native_mut(&mut self) -> &mut SkShaper {
+        unsafe { &mut *self.0 }
+    }
+}
+
+impl Drop for Shaper {
+    fn drop(&mut self) {
+        unsafe { C_SkShaper_delete(self.0) }
+    }
+}
+
+impl Default for Shaper {
+    fn default() -> Self {
+        Shaper::new()
+    }
+}
+
+/// A run of glyphs that share the same font and bidi level.
+#[derive(Clone, Debug)]
+pub struct Run {
+    pub font: Font,
+    /// The bidi embedding level, odd levels are right to left.
+    pub bidi_level: u8,
+    pub advance: Vector,
+    /// The range of UTF-8 bytes in the text this run was shaped from.
+    pub utf8_range: Range<usize>,
+    /// The index of the line this run is placed on.
+    pub line: usize,
+    pub glyphs: Vec<GlyphId>,
+    /// The glyph positions relative to the start of the line.
+    pub positions: Vec<Point>,
+    /// The UTF-8 byte offsets of the clusters the glyphs belong to.
+    pub clusters: Vec<usize>,
+}
+
+impl Shaper {
+    /// Creates a shaper that uses HarfBuzz and ICU to itemize the text by bidi level and
+    /// script before shaping it.
+    pub fn new() -> Shaper {
+        Shaper(unsafe { C_SkShaper_Make() })
+    }
+
+    /// Creates a shaper that maps characters to glyphs one by one.
+    pub fn new_primitive() -> Shaper {
+        Shaper(unsafe { C_SkShaper_MakePrimitive() })
+    }
+
+    /// Shapes and wraps `text` to lines of the given `width` and returns a text blob that
+    /// starts at `offset`, and the point where the text ends.
+    ///
+    /// Returns `None` if no glyphs were produced.
+    pub fn shape(
+        &self,
+        text: &str,
+        font: &Font,
+        left_to_right: bool,
+        width: scalar,
+        offset: impl Into<Point>,
+    ) -> Option<(TextBlob, Point)> {
+        let bytes = text.as_bytes();
+        let mut end_point = Point::default();
+        TextBlob::from_ptr(unsafe {
+            C_SkShaper_shape(
+                self.native(),
+                bytes.as_ptr() as _,
+                bytes.len(),
+                font.native(),
+                left_to_right,
+                width,
+                offset.into().native(),
+                end_point.native_mut(),
+            )
+        })
+        .map(|blob| (blob, end_point))
+    }
+
+    /// Shapes and wraps `text` to lines of the given `width` and returns the resulting runs in
+    /// visual order.
+    pub fn shape_runs(
+        &self,
+        text: &str,
+        font: &Font,
+        left_to_right: bool,
+        width: scalar,
+    ) -> Vec<Run> {
+        let bytes = text.as_bytes();
+        let mut runs: Vec<Run> = Vec::new();
+        unsafe {
+            C_SkShaper_shapeRuns(
+                self.native(),
+                bytes.as_ptr() as _,
+                bytes.len(),
+                font.native(),
+                left_to_right,
+                width,
+                &mut runs as *mut Vec<Run> as _,
+                Some(commit_run_trampoline),
+            )
+        }
+        runs
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+unsafe extern "C" fn commit_run_trampoline(
+    data: *mut c_void,
+    font: *const SkFont,
+    bidi_level: u8,
+    advance: *const SkPoint,
+    utf8_begin: usize,
+    utf8_end: usize,
+    line: usize,
+    glyph_count: usize,
+    glyphs: *const GlyphId,
+    positions: *const SkPoint,
+    clusters: *const u32,
+) {
+    // the glyph arrays of an empty run may be null.
+    let (glyphs, positions, clusters) = if glyph_count > 0 {
+        (
+            slice::from_raw_parts(glyphs, glyph_count),
+            slice::from_raw_parts(positions as *const Point, glyph_count),
+            slice::from_raw_parts(clusters, glyph_count),
+        )
+    } else {
+        (&[][..], &[][..], &[][..])
+    };
+    let runs = &mut *(data as *mut Vec<Run>);
+    runs.push(Run {
+        font: Font::from_native_ref(&*font).clone(),
+        bidi_level,
+        advance: Vector::from_native(*advance),
+        utf8_range: utf8_begin..utf8_end,
+        line,
+        glyphs: glyphs.to_vec(),
+        positions: positions.to_vec(),
+        clusters: clusters.iter().map(|c| *c as usize).collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Shaper;
+    use crate::{Font, FontStyle, Typeface};
+
+    fn font() -> Font {
+        Font::from_typeface(
+            &Typeface::from_name("", FontStyle::default()).unwrap(),
+            16.0,
+        )
+    }
+
+    #[test]
+    fn shape_to_text_blob() {
+        let shaper = Shaper::new();
+        let (blob, end) = shaper
+            .shape("Hello, World", &font(), true, 1000.0, (10, 10))
+            .unwrap();
+        assert!(!blob.bounds().is_empty());
+        assert!(end.x > 10.0);
+        assert!(shaper.shape("", &font(), true, 1000.0, (0, 0)).is_none());
+    }
+
+    #[test]
+    fn runs_cover_the_text() {
+        let text = "Hello, World";
+        let runs = Shaper::new().shape_runs(text, &font(), true, 1000.0);
+        assert!(!runs.is_empty());
+        assert_eq!(0, runs[0].utf8_range.start);
+        assert_eq!(text.len(), runs.last().unwrap().utf8_range.end);
+        for run in &runs {
+            assert_eq!(0, run.line);
+            assert_eq!(0, run.bidi_level % 2);
+            assert_eq!(run.glyphs.len(), run.positions.len());
+            assert_eq!(run.glyphs.len(), run.clusters.len());
+        }
+    }
+
+    #[test]
+    fn right_to_left_text_has_odd_bidi_levels() {
+        let runs =
+            Shaper::new().shape_runs("\u{05e9}\u{05dc}\u{05d5}\u{05dd}", &font(), false, 1000.0);
+        assert!(runs.iter().all(|run| run.bidi_level % 2 == 1));
+    }
+
+    #[test]
+    fn wrapping_produces_multiple_lines() {
+        let runs = Shaper::new().shape_runs("Hello World Hello World", &font(), true, 50.0);
+        assert!(runs.iter().any(|run| run.line > 0));
+    }
+}
@@ -0,0 +1,5 @@
+mod animation;
+pub use self::animation::*;
+
+mod resource_provider;
+pub use self::resource_provider::*;
@@ -0,0 +1,307 @@
+use super::{new_native_resource_provider, ProviderPanic, ResourceProvider};
+use crate::interop;
+use crate::prelude::*;
+use crate::{scalar, Canvas, FontMgr, Rect, Size};
+use skia_bindings::{
+    skottie_Animation, C_skottie_Animation_Make, C_skottie_Animation_duration,
+    C_skottie_Animation_ref, C_skottie_Animation_render, C_skottie_Animation_seek,
+    C_skottie_Animation_size, C_skottie_Animation_unique, C_skottie_Animation_unref,
+    C_skottie_Animation_version,
+};
+use std::path::Path;
+use std::{fs, ptr, str};
+
+/// A Lottie animation.
+#[derive(Clone)]
+pub struct Animation {
+    native: RCHandle<skottie_Animation>,
+    fps: Option<scalar>,
+}
+
+impl NativeRefCounted for skottie_Animation {
+    fn _ref(&self) {
+        unsafe { C_skottie_Animation_ref(self) }
+    }
+
+    fn _unref(&self) {
+        unsafe { C_skottie_Animation_unref(self) }
+    }
+
+    fn unique(&self) -> bool {
+        unsafe { C_skottie_Animation_unique(self) }
+    }
+}
+
+impl Animation {
+    /// Loads an animation from its JSON representation.
+    pub fn from_data(data: &[u8]) -> Option<Animation> {
+        Builder::new().make(data)
+    }
+
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(json: &str) -> Option<Animation> {
+        Self::from_data(json.as_bytes())
+    }
+
+    pub fn from_file(path: impl AsRef<Path>) -> Option<Animation> {
+        Self::from_data(&fs::read(path).ok()?)
+    }
+
+    /// The Bodymovin version the animation was exported with.
+    pub fn version(&self) -> String {
+        let mut version = interop::String::default();
+        unsafe { C_skottie_Animation_version(self.native.native(), version.native_mut()) };
+        version.to_string()
+    }
+
+    /// The duration of the animation in seconds.
+    pub fn duration(&self) -> scalar {
+        unsafe { C_skottie_Animation_duration(self.native.native()) }
+    }
+
+    /// The number of frames per second, or `None` if the frame rate could not be read from the
+    /// JSON representation of the animation.
+    pub fn fps(&self) -> Option<scalar> {
+        self.fps
+    }
+
+    /// The intrinsic size of the animation.
+    pub fn size(&self) -> Size {
+        let mut size = Size::default();
+        unsafe { C_skottie_Animation_size(self.native.native(), size.native_mut()) };
+        size
+    }
+
+    /// Updates the animation state to the normalized time `t` in the range `0..=1`.
+    pub fn seek(&mut self, t: scalar) {
+        unsafe { C_skottie_Animation_seek(self.native.native_mut(), t) }
+    }
+
+    /// Updates the animation state to the frame `frame`, which is relative to the first frame
+    /// of the animation and may be fractional.
+    ///
+    /// Does nothing if the frame rate of the animation is not known.
+    pub fn seek_frame(&mut self, frame: f64) {
+        let fps = match self.fps {
+            Some(fps) => fps,
+            None => return,
+        };
+        let frames = f64::from(self.duration()) * f64::from(fps);
+        if frames > 0.0 {
+            self.seek((frame / frames) as scalar)
+        }
+    }
+
+    /// Draws the current animation frame, scaled to `dst` or at the origin with the intrinsic
+    /// size of the animation if `dst` is `None`.
+    pub fn render(&self, canvas: &mut Canvas, dst: Option<&Rect>) {
+        unsafe {
+            C_skottie_Animation_render(
+                self.native.native(),
+                canvas.native_mut(),
+                dst.native_ptr_or_null(),
+            )
+        }
+    }
+}
+
+/// Configures how animations are loaded.
+#[derive(Default)]
+pub struct Builder {
+    font_mgr: Option<FontMgr>,
+    resource_provider: Option<Box<dyn ResourceProvider>>,
+}
+
+impl Builder {
+    pub fn new() -> Builder {
+        Self::default()
+    }
+
+    /// The font manager that resolves the fonts of text layers.
+    pub fn font_mgr(mut self, font_mgr: FontMgr) -> Builder {
+        self.font_mgr = Some(font_mgr);
+        self
+    }
+
+    /// The provider of external images and fonts.
+    pub fn resource_provider(
+        mut self,
+        resource_provider: impl ResourceProvider + 'static,
+    ) -> Builder {
+        self.resource_provider = Some(Box::new(resource_provider));
+        self
+    }
+
+    pub fn make(self, data: &[u8]) -> Option<Animation> {
+        // m75's Animation does not expose the frame rate.
+        let fps = str::from_utf8(data).ok().and_then(frame_rate);
+        let panic = ProviderPanic::default();
+        let resource_provider = match self.resource_provider {
+            Some(provider) => new_native_resource_provider(provider, panic.clone()),
+            None => ptr::null_mut(),
+        };
+        let native = RCHandle::from_ptr(unsafe {
+            C_skottie_Animation_Make(
+                data.as_ptr() as _,
+                data.len(),
+                self.font_mgr.shared_ptr() as _,
+                resource_provider,
+            )
+        });
+        if let Some(panic) = panic.borrow_mut().take() {
+            std::panic::resume_unwind(panic)
+        }
+        Some(Animation {
+            native: native?,
+            fps,
+        })
+    }
+
+    pub fn make_from_str(self, json: &str) -> Option<Animation> {
+        self.make(json.as_bytes())
+    }
+}
+
+/// Returns the value of the top level `"fr"` (frame rate) member of a Lottie JSON document.
+fn frame_rate(json: &str) -> Option<scalar> {
+    let bytes = json.as_bytes();
+    let mut depth = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'{' | b'[' => depth += 1,
+            b'}' | b']' => depth -= 1,
+            b'"' => {
+                let start = i + 1;
+                i = start;
+                while i < bytes.len() && bytes[i] != b'"' {
+                    if bytes[i] == b'\\' {
+                        i += 1;
+                    }
+                    i += 1;
+                }
+                if i >= bytes.len() {
+                    return None;
+                }
+                if depth == 1 && &bytes[start..i] == b"fr" {
+                    let value = json[i + 1..].trim_start();
+                    if value.starts_with(':') {
+                        let number: String = value[1..]
+                            .trim_start()
+                            .chars()
+                            .take_while(|c| c.is_ascii_digit() || "+-.eE".contains(*c))
+                            .collect();
+                        return number.parse().ok();
+                    }
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{frame_rate, Animation, Builder};
+    use crate::skottie::ResourceProvider;
+    use crate::{Color, Data, Rect, Size, Surface};
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    const RED_SQUARE: &str = r##"{
+        "v": "5.1.1", "fr": 30, "ip": 0, "op": 60, "w": 40, "h": 20,
+        "layers": [{
+            "ty": 1, "ip": 0, "op": 60, "st": 0, "sc": "#ff0000", "sw": 40, "sh": 20,
+            "ks": { "o": { "a": 0, "k": 100 } }
+        }]
+    }"##;
+
+    #[test]
+    #[allow(clippy::float_cmp)]
+    fn frame_rate_of_top_level_member() {
+        assert_eq!(Some(30.0), frame_rate(RED_SQUARE));
+        assert_eq!(
+            Some(29.97),
+            frame_rate(r#"{"layers":[{"fr":1}],"fr" : 29.97}"#)
+        );
+        assert_eq!(None, frame_rate(r#"{"layers":[{"fr":1}]}"#));
+    }
+
+    #[test]
+    #[allow(clippy::float_cmp)]
+    fn load_and_render() {
+        let mut animation = Animation::from_str(RED_SQUARE).unwrap();
+        assert_eq!("5.1.1", animation.version());
+        assert_eq!(2.0, animation.duration());
+        assert_eq!(Some(30.0), animation.fps());
+        assert_eq!(Size::new(40.0, 20.0), animation.size());
+
+        animation.seek_frame(30.0);
+        let mut surface = Surface::new_raster_n32_premul((40, 20)).unwrap();
+        animation.render(surface.canvas(), Some(&Rect::from_wh(40.0, 20.0)));
+        let pixels = surface.peek_pixels().unwrap();
+        assert_eq!(Color::RED, pixels.get_color((20, 10)));
+    }
+
+    #[test]
+    fn unknown_frame_rate_does_not_prevent_loading() {
+        // the frame rate member is escaped, which is not recognized when reading the frame rate.
+        let json = RED_SQUARE.replace(r#""fr""#, r#""f\u0072""#);
+        let mut animation = Animation::from_str(&json).unwrap();
+        assert_eq!(None, animation.fps());
+        animation.seek_frame(30.0);
+    }
+
+    #[test]
+    fn invalid_animation() {
+        assert!(Animation::from_str("{}").is_none());
+        assert!(Animation::from_str("not json").is_none());
+    }
+
+    #[test]
+    fn resource_provider_is_dropped_after_loading() {
+        struct Provider(Rc<RefCell<Vec<String>>>);
+        impl ResourceProvider for Provider {
+            fn load(&self, path: &str, name: &str) -> Option<Data> {
+                self.0.borrow_mut().push(format!("{}{}", path, name));
+                None
+            }
+        }
+
+        let requests = Rc::new(RefCell::new(Vec::new()));
+        let json = r#"{
+            "v": "5.1.1", "fr": 30, "ip": 0, "op": 60, "w": 40, "h": 20,
+            "assets": [{ "id": "image_0", "w": 40, "h": 20, "u": "images/", "p": "img_0.png" }],
+            "layers": [{ "ty": 2, "refId": "image_0", "ip": 0, "op": 60, "st": 0, "ks": {} }]
+        }"#;
+        let animation = Builder::new()
+            .resource_provider(Provider(requests.clone()))
+            .make_from_str(json);
+        assert!(animation.is_some());
+        assert_eq!(vec!["images/img_0.png".to_string()], *requests.borrow());
+        drop(animation);
+        assert_eq!(1, Rc::strong_count(&requests));
+    }
+
+    #[test]
+    #[should_panic(expected = "load panicked")]
+    fn resource_provider_panics_are_resumed_after_loading() {
+        struct Provider;
+        impl ResourceProvider for Provider {
+            fn load(&self, _path: &str, _name: &str) -> Option<Data> {
+                panic!("load panicked")
+            }
+        }
+
+        let json = r#"{
+            "v": "5.1.1", "fr": 30, "ip": 0, "op": 60, "w": 40, "h": 20,
+            "assets": [{ "id": "image_0", "w": 40, "h": 20, "u": "images/", "p": "img_0.png" }],
+            "layers": [{ "ty": 2, "refId": "image_0", "ip": 0, "op": 60, "st": 0, "ks": {} }]
+        }"#;
+        Builder::new()
+            .resource_provider(Provider)
+            .make_from_str(json);
+    }
+}
@@ -0,0 +1,117 @@
+use crate::prelude::*;
+use crate::{Data, Image};
+use skia_bindings::{skottie_ResourceProvider, C_RustResourceProvider_new, SkData, SkImage};
+use std::any::Any;
+use std::cell::RefCell;
+use std::ffi::{c_void, CStr};
+use std::os::raw::c_char;
+use std::panic::{self, AssertUnwindSafe};
+use std::rc::Rc;
+
+/// Provides the external resources a Lottie animation refers to.
+///
+/// All functions receive the path and the name of a resource like they are specified in the
+/// animation and return `None` if the resource is not available.
+pub trait ResourceProvider {
+    /// Loads a generic resource.
+    fn load(&self, _path: &str, _name: &str) -> Option<Data> {
+        None
+    }
+
+    /// Loads an image, by default the encoded image returned by `load()` is decoded.
+    fn load_image(&self, path: &str, name: &str) -> Option<Image> {
+        self.load(path, name)
+            .and_then(|data| Image::from_encoded(&data, None))
+    }
+
+    /// Loads the font data for the font `name` that is specified with `url`.
+    fn load_font(&self, _name: &str, _url: &str) -> Option<Data> {
+        None
+    }
+}
+
+/// The panic of a resource provider, resumed after the animation is built.
+pub(crate) type ProviderPanic = Rc<RefCell<Option<Box<dyn Any + Send>>>>;
+
+struct State {
+    provider: Box<dyn ResourceProvider>,
+    panic: ProviderPanic,
+}
+
+/// Creates a native resource provider that forwards all requests to `provider` and
+/// drops it when it's not used anymore.
+///
+/// A panic of `provider` is stored in `panic`, and no requests are forwarded after that.
+pub(crate) fn new_native_resource_provider(
+    provider: Box<dyn ResourceProvider>,
+    panic: ProviderPanic,
+) -> *mut skottie_ResourceProvider {
+    let data = Box::into_raw(Box::new(State { provider, panic }));
+    unsafe {
+        C_RustResourceProvider_new(
+            data as _,
+            Some(load_trampoline),
+            Some(load_image_trampoline),
+            Some(load_font_trampoline),
+            Some(drop_trampoline),
+        )
+    }
+}
+
+/// Invokes `f` with the provider, unless it panicked before.
+unsafe fn forward<R>(
+    data: *mut c_void,
+    f: impl FnOnce(&dyn ResourceProvider) -> Option<R>,
+) -> Option<R> {
+    let state = &*(data as *mut State);
+    if state.panic.borrow().is_some() {
+        return None;
+    }
+    let provider = state.provider.as_ref();
+    panic::catch_unwind(AssertUnwindSafe(|| f(provider))).unwrap_or_else(|panic| {
+        *state.panic.borrow_mut() = Some(panic);
+        None
+    })
+}
+
+unsafe fn to_str<'a>(str: *const c_char) -> std::borrow::Cow<'a, str> {
+    if str.is_null() {
+        "".into()
+    } else {
+        CStr::from_ptr(str).to_string_lossy()
+    }
+}
+
+unsafe extern "C" fn load_trampoline(
+    data: *mut c_void,
+    path: *const c_char,
+    name: *const c_char,
+) -> *mut SkData {
+    forward(data, |provider| provider.load(&to_str(path), &to_str(name))).shared_ptr() as _
+}
+
+unsafe extern "C" fn load_image_trampoline(
+    data: *mut c_void,
+    path: *const c_char,
+    name: *const c_char,
+) -> *mut SkImage {
+    forward(data, |provider| {
+        provider.load_image(&to_str(path), &to_str(name))
+    })
+    .shared_ptr() as _
+}
+
+unsafe extern "C" fn load_font_trampoline(
+    data: *mut c_void,
+    name: *const c_char,
+    url: *const c_char,
+) -> *mut SkData {
+    forward(data, |provider| {
+        provider.load_font(&to_str(name), &to_str(url))
+    })
+    .shared_ptr() as _
+}
+
+unsafe extern "C" fn drop_trampoline(data: *mut c_void) {
+    drop(Box::from_raw(data as *mut State))
+}
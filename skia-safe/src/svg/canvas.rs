@@ -1,17 +1,20 @@
-use crate::interop::DynamicMemoryWStream;
+use crate::document::output::{self, OutputStream};
 use crate::prelude::*;
 use crate::{Data, Rect};
 use skia_bindings::{C_SkCanvas_delete, C_SkSVGCanvas_Make, SkCanvas};
+use std::io::{self, Write};
 use std::ops::{Deref, DerefMut};
-use std::pin::Pin;
 use std::ptr;
 
-pub struct Canvas {
+pub struct Canvas<Output = output::Memory> {
     canvas: *mut SkCanvas,
-    stream: Pin<Box<DynamicMemoryWStream>>,
+    output: Output,
 }
 
-impl Drop for Canvas {
+/// An SVG canvas that streams the SVG to a Rust writer.
+pub type WriterCanvas<'a> = Canvas<output::Writer<'a>>;
+
+impl<O> Drop for Canvas<O> {
     fn drop(&mut self) {
         unsafe {
             C_SkCanvas_delete(self.canvas);
@@ -19,7 +22,7 @@ impl Drop for Canvas {
     }
 }
 
-impl Deref for Canvas {
+impl<O> Deref for Canvas<O> {
     type Target = crate::Canvas;
 
     fn deref(&self) -> &Self::Target {
@@ -27,32 +30,55 @@ impl Deref for Canvas {
     }
 }
 
-impl DerefMut for Canvas {
+impl<O> DerefMut for Canvas<O> {
     fn deref_mut(&mut self) -> &mut Self::Target {
         crate::Canvas::borrow_from_native(unsafe { &mut *self.canvas })
     }
 }
 
+impl<O: OutputStream> Canvas<O> {
+    fn new_to_output(bounds: impl AsRef<Rect>, mut output: O) -> Canvas<O> {
+        let bounds = bounds.as_ref();
+        let canvas = unsafe { C_SkSVGCanvas_Make(bounds.native(), output.stream_mut()) };
+        Canvas { canvas, output }
+    }
+
+    // note: flushing canvas + XMLStreamWriter does not seem to work,
+    // we have to delete the canvas and destruct the stream writer
+    // to get all data out _and_ keep the referential integrity.
+    fn delete_canvas(&mut self) {
+        unsafe {
+            C_SkCanvas_delete(self.canvas);
+        }
+        self.canvas = ptr::null_mut();
+    }
+}
+
 impl Canvas {
     /// Creates a new SVG canvas.
     pub fn new(bounds: impl AsRef<Rect>) -> Canvas {
-        let bounds = bounds.as_ref();
-        let mut stream = Box::pin(DynamicMemoryWStream::new());
-        let canvas = unsafe { C_SkSVGCanvas_Make(bounds.native(), &mut stream.native_mut()._base) };
-        Canvas { canvas, stream }
+        Self::new_to_output(bounds, output::Memory::new())
     }
 
     /// Ends the Canvas drawing and returns the resulting SVG.
     /// TODO: rename to into_svg() or into_svg_data()?
     pub fn end(mut self) -> Data {
-        // note: flushing canvas + XMLStreamWriter does not seem to work,
-        // we have to delete the canvas and destruct the stream writer
-        // to get all data out _and_ keep the referential integrity.
-        unsafe {
-            C_SkCanvas_delete(self.canvas);
-        }
-        self.canvas = ptr::null_mut();
-        self.stream.detach_as_data()
+        self.delete_canvas();
+        self.output.0.detach_as_data()
+    }
+}
+
+impl<'a> WriterCanvas<'a> {
+    /// Creates a new SVG canvas that streams the SVG to `writer`.
+    pub fn new_to_writer(bounds: impl AsRef<Rect>, writer: impl Write + 'a) -> WriterCanvas<'a> {
+        Self::new_to_output(bounds, output::Writer::new(writer))
+    }
+
+    /// Ends the Canvas drawing and flushes the writer. Returns the first I/O error that
+    /// occurred while the SVG was written.
+    pub fn end(mut self) -> io::Result<()> {
+        self.delete_canvas();
+        self.output.0.finish()
     }
 }
 
@@ -78,3 +104,17 @@ fn test_svg_without_ending() {
     let paint = Paint::default();
     canvas.draw_circle((10, 10), 10.0, &paint);
 }
+
+#[test]
+fn test_svg_to_writer() {
+    use crate::Paint;
+
+    let mut bytes = Vec::new();
+    {
+        let mut canvas = WriterCanvas::new_to_writer(&Rect::from_size((20, 20)), &mut bytes);
+        canvas.draw_circle((10, 10), 10.0, &Paint::default());
+        canvas.end().unwrap();
+    }
+    let contents = String::from_utf8_lossy(&bytes);
+    assert!(contents.contains(r#"</svg>"#));
+}
@@ -11,5 +11,12 @@ mod null_canvas;
 pub use null_canvas::*;
 
 pub mod parse_path;
+
+mod picture_ops;
+pub use picture_ops::*;
+
+mod rust_canvas;
+pub use rust_canvas::*;
+
 pub mod shadow_utils;
 pub mod text_utils;
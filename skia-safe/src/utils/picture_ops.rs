@@ -0,0 +1,864 @@
+use crate::canvas::{PointMode, SrcRectConstraint};
+use crate::prelude::*;
+use crate::utils::{CanvasHandler, RustCanvas};
+use crate::{
+    scalar, ClipOp, IRect, Image, Matrix, Paint, Path, Picture, Point, RRect, Rect, Region,
+    RoundOut, TextBlob,
+};
+use skia_bindings::C_SkPaint_computeFastBounds;
+use std::cell::Cell;
+use std::fmt;
+use std::rc::Rc;
+
+/// A recorded picture op.
+///
+/// Draw calls that have no equivalent here, like vertices or image lattices, are
+/// represented by `Other`.
+///
+/// Ops compare equal if they draw the same, images and text blobs are compared by their
+/// unique ids.
+#[derive(Clone)]
+pub enum DrawOp {
+    Save,
+    SaveLayer {
+        bounds: Option<Rect>,
+        paint: Option<Paint>,
+    },
+    Restore,
+    Concat(Matrix),
+    SetMatrix(Matrix),
+    ClipRect {
+        rect: Rect,
+        op: ClipOp,
+        do_anti_alias: bool,
+    },
+    ClipRRect {
+        rrect: RRect,
+        op: ClipOp,
+        do_anti_alias: bool,
+    },
+    ClipPath {
+        path: Path,
+        op: ClipOp,
+        do_anti_alias: bool,
+    },
+    ClipRegion {
+        region: Region,
+        op: ClipOp,
+    },
+    DrawPaint(Paint),
+    DrawPoints {
+        mode: PointMode,
+        points: Vec<Point>,
+        paint: Paint,
+    },
+    DrawRect {
+        rect: Rect,
+        paint: Paint,
+    },
+    DrawRRect {
+        rrect: RRect,
+        paint: Paint,
+    },
+    DrawDRRect {
+        outer: RRect,
+        inner: RRect,
+        paint: Paint,
+    },
+    DrawOval {
+        oval: Rect,
+        paint: Paint,
+    },
+    DrawArc {
+        oval: Rect,
+        start_angle: scalar,
+        sweep_angle: scalar,
+        use_center: bool,
+        paint: Paint,
+    },
+    DrawPath {
+        path: Path,
+        paint: Paint,
+    },
+    DrawRegion {
+        region: Region,
+        paint: Paint,
+    },
+    DrawTextBlob {
+        blob: TextBlob,
+        origin: Point,
+        paint: Paint,
+    },
+    DrawImage {
+        image: Image,
+        left_top: Point,
+        paint: Option<Paint>,
+    },
+    DrawImageRect {
+        image: Image,
+        src: Option<Rect>,
+        dst: Rect,
+        paint: Option<Paint>,
+        constraint: SrcRectConstraint,
+    },
+    Other,
+}
+
+impl PartialEq for DrawOp {
+    #[allow(clippy::float_cmp)]
+    fn eq(&self, other: &Self) -> bool {
+        use DrawOp::*;
+        match (self, other) {
+            (Save, Save) | (Restore, Restore) | (Other, Other) => true,
+            (
+                SaveLayer { bounds, paint },
+                SaveLayer {
+                    bounds: bounds2,
+                    paint: paint2,
+                },
+            ) => bounds == bounds2 && paint == paint2,
+            (Concat(matrix), Concat(matrix2)) | (SetMatrix(matrix), SetMatrix(matrix2)) => {
+                matrix == matrix2
+            }
+            (
+                ClipRect {
+                    rect,
+                    op,
+                    do_anti_alias,
+                },
+                ClipRect {
+                    rect: rect2,
+                    op: op2,
+                    do_anti_alias: do_anti_alias2,
+                },
+            ) => rect == rect2 && op == op2 && do_anti_alias == do_anti_alias2,
+            (
+                ClipRRect {
+                    rrect,
+                    op,
+                    do_anti_alias,
+                },
+                ClipRRect {
+                    rrect: rrect2,
+                    op: op2,
+                    do_anti_alias: do_anti_alias2,
+                },
+            ) => rrect == rrect2 && op == op2 && do_anti_alias == do_anti_alias2,
+            (
+                ClipPath {
+                    path,
+                    op,
+                    do_anti_alias,
+                },
+                ClipPath {
+                    path: path2,
+                    op: op2,
+                    do_anti_alias: do_anti_alias2,
+                },
+            ) => path == path2 && op == op2 && do_anti_alias == do_anti_alias2,
+            (
+                ClipRegion { region, op },
+                ClipRegion {
+                    region: region2,
+                    op: op2,
+                },
+            ) => region == region2 && op == op2,
+            (DrawPaint(paint), DrawPaint(paint2)) => paint == paint2,
+            (
+                DrawPoints {
+                    mode,
+                    points,
+                    paint,
+                },
+                DrawPoints {
+                    mode: mode2,
+                    points: points2,
+                    paint: paint2,
+                },
+            ) => mode == mode2 && points == points2 && paint == paint2,
+            (
+                DrawRect { rect, paint },
+                DrawRect {
+                    rect: rect2,
+                    paint: paint2,
+                },
+            ) => rect == rect2 && paint == paint2,
+            (
+                DrawRRect { rrect, paint },
+                DrawRRect {
+                    rrect: rrect2,
+                    paint: paint2,
+                },
+            ) => rrect == rrect2 && paint == paint2,
+            (
+                DrawDRRect {
+                    outer,
+                    inner,
+                    paint,
+                },
+                DrawDRRect {
+                    outer: outer2,
+                    inner: inner2,
+                    paint: paint2,
+                },
+            ) => outer == outer2 && inner == inner2 && paint == paint2,
+            (
+                DrawOval { oval, paint },
+                DrawOval {
+                    oval: oval2,
+                    paint: paint2,
+                },
+            ) => oval == oval2 && paint == paint2,
+            (
+                DrawArc {
+                    oval,
+                    start_angle,
+                    sweep_angle,
+                    use_center,
+                    paint,
+                },
+                DrawArc {
+                    oval: oval2,
+                    start_angle: start_angle2,
+                    sweep_angle: sweep_angle2,
+                    use_center: use_center2,
+                    paint: paint2,
+                },
+            ) => {
+                oval == oval2
+                    && start_angle == start_angle2
+                    && sweep_angle == sweep_angle2
+                    && use_center == use_center2
+                    && paint == paint2
+            }
+            (
+                DrawPath { path, paint },
+                DrawPath {
+                    path: path2,
+                    paint: paint2,
+                },
+            ) => path == path2 && paint == paint2,
+            (
+                DrawRegion { region, paint },
+                DrawRegion {
+                    region: region2,
+                    paint: paint2,
+                },
+            ) => region == region2 && paint == paint2,
+            (
+                DrawTextBlob {
+                    blob,
+                    origin,
+                    paint,
+                },
+                DrawTextBlob {
+                    blob: blob2,
+                    origin: origin2,
+                    paint: paint2,
+                },
+            ) => blob.unique_id() == blob2.unique_id() && origin == origin2 && paint == paint2,
+            (
+                DrawImage {
+                    image,
+                    left_top,
+                    paint,
+                },
+                DrawImage {
+                    image: image2,
+                    left_top: left_top2,
+                    paint: paint2,
+                },
+            ) => {
+                image.unique_id() == image2.unique_id() && left_top == left_top2 && paint == paint2
+            }
+            (
+                DrawImageRect {
+                    image,
+                    src,
+                    dst,
+                    paint,
+                    constraint,
+                },
+                DrawImageRect {
+                    image: image2,
+                    src: src2,
+                    dst: dst2,
+                    paint: paint2,
+                    constraint: constraint2,
+                },
+            ) => {
+                image.unique_id() == image2.unique_id()
+                    && src == src2
+                    && dst == dst2
+                    && paint == paint2
+                    && constraint == constraint2
+            }
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Debug for DrawOp {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use DrawOp::*;
+        match self {
+            Save => f.write_str("Save"),
+            SaveLayer { bounds, paint } => f
+                .debug_struct("SaveLayer")
+                .field("bounds", bounds)
+                .field("paint", &paint.as_ref().map(Summary))
+                .finish(),
+            Restore => f.write_str("Restore"),
+            Concat(matrix) => f.debug_tuple("Concat").field(matrix).finish(),
+            SetMatrix(matrix) => f.debug_tuple("SetMatrix").field(matrix).finish(),
+            ClipRect {
+                rect,
+                op,
+                do_anti_alias,
+            } => f
+                .debug_struct("ClipRect")
+                .field("rect", rect)
+                .field("op", op)
+                .field("do_anti_alias", do_anti_alias)
+                .finish(),
+            ClipRRect {
+                rrect,
+                op,
+                do_anti_alias,
+            } => f
+                .debug_struct("ClipRRect")
+                .field("rrect", &Summary(rrect))
+                .field("op", op)
+                .field("do_anti_alias", do_anti_alias)
+                .finish(),
+            ClipPath {
+                path,
+                op,
+                do_anti_alias,
+            } => f
+                .debug_struct("ClipPath")
+                .field("path", &Summary(path))
+                .field("op", op)
+                .field("do_anti_alias", do_anti_alias)
+                .finish(),
+            ClipRegion { region, op } => f
+                .debug_struct("ClipRegion")
+                .field("region", &Summary(region))
+                .field("op", op)
+                .finish(),
+            DrawPaint(paint) => f.debug_tuple("DrawPaint").field(&Summary(paint)).finish(),
+            DrawPoints {
+                mode,
+                points,
+                paint,
+            } => f
+                .debug_struct("DrawPoints")
+                .field("mode", mode)
+                .field("points", points)
+                .field("paint", &Summary(paint))
+                .finish(),
+            DrawRect { rect, paint } => f
+                .debug_struct("DrawRect")
+                .field("rect", rect)
+                .field("paint", &Summary(paint))
+                .finish(),
+            DrawRRect { rrect, paint } => f
+                .debug_struct("DrawRRect")
+                .field("rrect", &Summary(rrect))
+                .field("paint", &Summary(paint))
+                .finish(),
+            DrawDRRect {
+                outer,
+                inner,
+                paint,
+            } => f
+                .debug_struct("DrawDRRect")
+                .field("outer", &Summary(outer))
+                .field("inner", &Summary(inner))
+                .field("paint", &Summary(paint))
+                .finish(),
+            DrawOval { oval, paint } => f
+                .debug_struct("DrawOval")
+                .field("oval", oval)
+                .field("paint", &Summary(paint))
+                .finish(),
+            DrawArc {
+                oval,
+                start_angle,
+                sweep_angle,
+                use_center,
+                paint,
+            } => f
+                .debug_struct("DrawArc")
+                .field("oval", oval)
+                .field("start_angle", start_angle)
+                .field("sweep_angle", sweep_angle)
+                .field("use_center", use_center)
+                .field("paint", &Summary(paint))
+                .finish(),
+            DrawPath { path, paint } => f
+                .debug_struct("DrawPath")
+                .field("path", &Summary(path))
+                .field("paint", &Summary(paint))
+                .finish(),
+            DrawRegion { region, paint } => f
+                .debug_struct("DrawRegion")
+                .field("region", &Summary(region))
+                .field("paint", &Summary(paint))
+                .finish(),
+            DrawTextBlob {
+                blob,
+                origin,
+                paint,
+            } => f
+                .debug_struct("DrawTextBlob")
+                .field("blob", &Summary(blob))
+                .field("origin", origin)
+                .field("paint", &Summary(paint))
+                .finish(),
+            DrawImage {
+                image,
+                left_top,
+                paint,
+            } => f
+                .debug_struct("DrawImage")
+                .field("image", &Summary(image))
+                .field("left_top", left_top)
+                .field("paint", &paint.as_ref().map(Summary))
+                .finish(),
+            DrawImageRect {
+                image,
+                src,
+                dst,
+                paint,
+                constraint,
+            } => f
+                .debug_struct("DrawImageRect")
+                .field("image", &Summary(image))
+                .field("src", src)
+                .field("dst", dst)
+                .field("paint", &paint.as_ref().map(Summary))
+                .field("constraint", constraint)
+                .finish(),
+            Other => f.write_str("Other"),
+        }
+    }
+}
+
+/// Formats the properties of the native types that identify them in debug output.
+struct Summary<'a, T>(&'a T);
+
+impl<'a> fmt::Debug for Summary<'a, Paint> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Paint")
+            .field("color", &self.0.color())
+            .field("style", &self.0.style())
+            .finish()
+    }
+}
+
+impl<'a> fmt::Debug for Summary<'a, Path> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Path")
+            .field("bounds", &self.0.bounds())
+            .field("points", &self.0.count_points())
+            .finish()
+    }
+}
+
+impl<'a> fmt::Debug for Summary<'a, Region> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Region")
+            .field("bounds", &self.0.bounds())
+            .finish()
+    }
+}
+
+impl<'a> fmt::Debug for Summary<'a, RRect> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("RRect")
+            .field("rect", self.0.rect())
+            .field("type", &self.0.get_type())
+            .finish()
+    }
+}
+
+impl<'a> fmt::Debug for Summary<'a, Image> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Image")
+            .field("unique_id", &self.0.unique_id())
+            .field("bounds", &self.0.bounds())
+            .finish()
+    }
+}
+
+impl<'a> fmt::Debug for Summary<'a, TextBlob> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("TextBlob")
+            .field("unique_id", &self.0.unique_id())
+            .field("bounds", self.0.bounds())
+            .finish()
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct PictureOp {
+    /// The index of the op in the picture. Ops of nested pictures share the index of the op
+    /// that draws the nested picture.
+    pub index: usize,
+    /// The conservative bounds of the op in the coordinate space of the picture, `None` if
+    /// the op does not draw or its bounds can not be computed.
+    pub bounds: Option<Rect>,
+    pub op: DrawOp,
+}
+
+impl Picture {
+    /// Plays back the picture and returns its ops in the order they are drawn.
+    pub fn ops(&self) -> Vec<PictureOp> {
+        let index = Rc::new(Cell::new(None));
+        let collector = OpCollector {
+            index: index.clone(),
+            matrix: Matrix::default(),
+            matrix_stack: Vec::new(),
+            ops: Vec::new(),
+            indexed: 0,
+        };
+        // the canvas clips to the cull rect, which may extend into negative coordinates.
+        let cull: IRect = self.cull_rect().round_out();
+        let mut canvas = RustCanvas::from_bounds(cull, collector);
+        self.playback_with_abort(&mut canvas, |i| {
+            index.set(Some(i));
+            false
+        });
+        let op_count = index.get().map(|i| i + 1).unwrap_or(0);
+        let mut collector = canvas.into_handler();
+        collector.fill_to(op_count);
+        collector.ops
+    }
+}
+
+struct OpCollector {
+    index: Rc<Cell<Option<usize>>>,
+    matrix: Matrix,
+    matrix_stack: Vec<Matrix>,
+    ops: Vec<PictureOp>,
+    // the number of op indices that are represented in ops.
+    indexed: usize,
+}
+
+impl OpCollector {
+    fn push(&mut self, op: DrawOp, bounds: Option<Rect>) {
+        let index = self.index.get().unwrap_or(0);
+        self.fill_to(index);
+        self.ops.push(PictureOp { index, bounds, op });
+        self.indexed = self.indexed.max(index + 1);
+    }
+
+    fn push_draw(&mut self, op: DrawOp, rect: Rect, paint: Option<&Paint>) {
+        let bounds = self.device_bounds(rect, paint);
+        self.push(op, bounds)
+    }
+
+    fn fill_to(&mut self, index: usize) {
+        while self.indexed < index {
+            self.ops.push(PictureOp {
+                index: self.indexed,
+                bounds: None,
+                op: DrawOp::Other,
+            });
+            self.indexed += 1;
+        }
+    }
+
+    fn device_bounds(&self, rect: Rect, paint: Option<&Paint>) -> Option<Rect> {
+        let rect = match paint {
+            Some(paint) => {
+                let mut bounds = Rect::default();
+                if !unsafe {
+                    C_SkPaint_computeFastBounds(paint.native(), rect.native(), bounds.native_mut())
+                } {
+                    return None;
+                }
+                bounds
+            }
+            None => rect,
+        };
+        Some(self.matrix.map_rect(rect).0)
+    }
+}
+
+impl CanvasHandler for OpCollector {
+    fn on_save(&mut self) {
+        self.matrix_stack.push(self.matrix);
+        self.push(DrawOp::Save, None)
+    }
+
+    fn on_save_layer(&mut self, bounds: Option<&Rect>, paint: Option<&Paint>) {
+        self.matrix_stack.push(self.matrix);
+        let op = DrawOp::SaveLayer {
+            bounds: bounds.copied(),
+            paint: paint.cloned(),
+        };
+        self.push(op, None)
+    }
+
+    fn on_restore(&mut self) {
+        if let Some(matrix) = self.matrix_stack.pop() {
+            self.matrix = matrix;
+        }
+        self.push(DrawOp::Restore, None)
+    }
+
+    fn on_concat(&mut self, matrix: &Matrix) {
+        self.matrix.pre_concat(matrix);
+        self.push(DrawOp::Concat(*matrix), None)
+    }
+
+    fn on_set_matrix(&mut self, matrix: &Matrix) {
+        self.matrix = *matrix;
+        self.push(DrawOp::SetMatrix(*matrix), None)
+    }
+
+    fn on_clip_rect(&mut self, rect: &Rect, op: ClipOp, do_anti_alias: bool) {
+        let op = DrawOp::ClipRect {
+            rect: *rect,
+            op,
+            do_anti_alias,
+        };
+        self.push(op, None)
+    }
+
+    fn on_clip_rrect(&mut self, rrect: &RRect, op: ClipOp, do_anti_alias: bool) {
+        let op = DrawOp::ClipRRect {
+            rrect: *rrect,
+            op,
+            do_anti_alias,
+        };
+        self.push(op, None)
+    }
+
+    fn on_clip_path(&mut self, path: &Path, op: ClipOp, do_anti_alias: bool) {
+        let op = DrawOp::ClipPath {
+            path: path.clone(),
+            op,
+            do_anti_alias,
+        };
+        self.push(op, None)
+    }
+
+    fn on_clip_region(&mut self, region: &Region, op: ClipOp) {
+        let op = DrawOp::ClipRegion {
+            region: region.clone(),
+            op,
+        };
+        self.push(op, None)
+    }
+
+    fn on_draw_paint(&mut self, paint: &Paint) {
+        self.push(DrawOp::DrawPaint(paint.clone()), None)
+    }
+
+    fn on_draw_points(&mut self, mode: PointMode, pts: &[Point], paint: &Paint) {
+        let mut rect = Rect::default();
+        rect.set_bounds(pts);
+        let op = DrawOp::DrawPoints {
+            mode,
+            points: pts.to_vec(),
+            paint: paint.clone(),
+        };
+        self.push_draw(op, rect, Some(paint))
+    }
+
+    fn on_draw_rect(&mut self, rect: &Rect, paint: &Paint) {
+        let op = DrawOp::DrawRect {
+            rect: *rect,
+            paint: paint.clone(),
+        };
+        self.push_draw(op, *rect, Some(paint))
+    }
+
+    fn on_draw_rrect(&mut self, rrect: &RRect, paint: &Paint) {
+        let op = DrawOp::DrawRRect {
+            rrect: *rrect,
+            paint: paint.clone(),
+        };
+        self.push_draw(op, *rrect.rect(), Some(paint))
+    }
+
+    fn on_draw_drrect(&mut self, outer: &RRect, inner: &RRect, paint: &Paint) {
+        let op = DrawOp::DrawDRRect {
+            outer: *outer,
+            inner: *inner,
+            paint: paint.clone(),
+        };
+        self.push_draw(op, *outer.rect(), Some(paint))
+    }
+
+    fn on_draw_oval(&mut self, oval: &Rect, paint: &Paint) {
+        let op = DrawOp::DrawOval {
+            oval: *oval,
+            paint: paint.clone(),
+        };
+        self.push_draw(op, *oval, Some(paint))
+    }
+
+    fn on_draw_arc(
+        &mut self,
+        oval: &Rect,
+        start_angle: scalar,
+        sweep_angle: scalar,
+        use_center: bool,
+        paint: &Paint,
+    ) {
+        let op = DrawOp::DrawArc {
+            oval: *oval,
+            start_angle,
+            sweep_angle,
+            use_center,
+            paint: paint.clone(),
+        };
+        self.push_draw(op, *oval, Some(paint))
+    }
+
+    fn on_draw_path(&mut self, path: &Path, paint: &Paint) {
+        let op = DrawOp::DrawPath {
+            path: path.clone(),
+            paint: paint.clone(),
+        };
+        self.push_draw(op, path.bounds(), Some(paint))
+    }
+
+    fn on_draw_region(&mut self, region: &Region, paint: &Paint) {
+        let op = DrawOp::DrawRegion {
+            region: region.clone(),
+            paint: paint.clone(),
+        };
+        self.push_draw(op, Rect::from(region.bounds()), Some(paint))
+    }
+
+    fn on_draw_text_blob(&mut self, blob: &TextBlob, origin: Point, paint: &Paint) {
+        let rect = blob.bounds().with_offset(origin);
+        let op = DrawOp::DrawTextBlob {
+            blob: blob.clone(),
+            origin,
+            paint: paint.clone(),
+        };
+        self.push_draw(op, rect, Some(paint))
+    }
+
+    fn on_draw_image(&mut self, image: &Image, left_top: Point, paint: Option<&Paint>) {
+        let rect = Rect::from(image.bounds()).with_offset(left_top);
+        let op = DrawOp::DrawImage {
+            image: image.clone(),
+            left_top,
+            paint: paint.cloned(),
+        };
+        self.push_draw(op, rect, paint)
+    }
+
+    fn on_draw_image_rect(
+        &mut self,
+        image: &Image,
+        src: Option<&Rect>,
+        dst: &Rect,
+        paint: Option<&Paint>,
+        constraint: SrcRectConstraint,
+    ) {
+        let op = DrawOp::DrawImageRect {
+            image: image.clone(),
+            src: src.copied(),
+            dst: *dst,
+            paint: paint.cloned(),
+            constraint,
+        };
+        self.push_draw(op, *dst, paint)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DrawOp;
+    use crate::{BBHFactory, ClipOp, Font, Paint, PictureRecorder, Rect, TextBlob, Typeface};
+
+    #[test]
+    #[allow(clippy::float_cmp)]
+    fn ops_are_listed_with_indices_and_bounds() {
+        let mut recorder = PictureRecorder::new();
+        let bounds = Rect::from_wh(100.0, 100.0);
+        let canvas = recorder.begin_recording(&bounds, None, None);
+        canvas.save();
+        canvas.translate((10.0, 10.0));
+        canvas.clip_rect(Rect::from_wh(50.0, 50.0), ClipOp::Intersect, false);
+        canvas.draw_rect(Rect::from_wh(20.0, 20.0), &Paint::default());
+        canvas.restore();
+        canvas.draw_circle((50.0, 50.0), 10.0, &Paint::default());
+        let picture = recorder.finish_recording_as_picture(None).unwrap();
+
+        let ops = picture.ops();
+        let indices: Vec<usize> = ops.iter().map(|op| op.index).collect();
+        assert_eq!(indices, [0, 1, 2, 3, 4, 5]);
+
+        match ops[1].op {
+            DrawOp::Concat(matrix) => assert_eq!(matrix.translate_x(), 10.0),
+            _ => panic!("expected Concat"),
+        }
+        match ops[3].op {
+            DrawOp::DrawRect { rect, .. } => assert_eq!(rect, Rect::from_wh(20.0, 20.0)),
+            _ => panic!("expected DrawRect"),
+        }
+        // the bounds are in picture coordinates.
+        assert_eq!(ops[3].bounds, Some(Rect::new(10.0, 10.0, 30.0, 30.0)));
+        assert!(ops[4].bounds.is_none());
+        match ops[5].op {
+            DrawOp::DrawOval { oval, .. } => assert_eq!(oval, Rect::new(40.0, 40.0, 60.0, 60.0)),
+            _ => panic!("expected DrawOval"),
+        }
+    }
+
+    #[test]
+    fn ops_at_negative_coordinates_are_listed() {
+        let mut recorder = PictureRecorder::new();
+        let bounds = Rect::new(-100.0, -100.0, 100.0, 100.0);
+        let mut bbh_factory = BBHFactory::new_rtree();
+        let canvas = recorder.begin_recording(&bounds, Some(&mut bbh_factory), None);
+        canvas.draw_rect(Rect::new(-90.0, -90.0, -70.0, -70.0), &Paint::default());
+        canvas.draw_circle((-50.0, -50.0), 10.0, &Paint::default());
+        canvas.draw_rect(Rect::new(10.0, 10.0, 20.0, 20.0), &Paint::default());
+        let picture = recorder.finish_recording_as_picture(None).unwrap();
+
+        let ops = picture.ops();
+        let indices: Vec<usize> = ops.iter().map(|op| op.index).collect();
+        assert_eq!(indices, [0, 1, 2]);
+        match ops[0].op {
+            DrawOp::DrawRect { rect, .. } => {
+                assert_eq!(rect, Rect::new(-90.0, -90.0, -70.0, -70.0))
+            }
+            _ => panic!("expected DrawRect"),
+        }
+        match ops[1].op {
+            DrawOp::DrawOval { oval, .. } => {
+                assert_eq!(oval, Rect::new(-60.0, -60.0, -40.0, -40.0))
+            }
+            _ => panic!("expected DrawOval"),
+        }
+        assert_eq!(ops[0].bounds, Some(Rect::new(-90.0, -90.0, -70.0, -70.0)));
+    }
+
+    #[test]
+    fn ops_can_be_compared_and_printed() {
+        let mut recorder = PictureRecorder::new();
+        let canvas = recorder.begin_recording(&Rect::from_wh(100.0, 100.0), None, None);
+        canvas.draw_rect(Rect::from_wh(20.0, 20.0), &Paint::default());
+        let font = Font::from_typeface(&Typeface::default(), 12.0);
+        let blob = TextBlob::from_str("Hi", &font).unwrap();
+        canvas.draw_text_blob(&blob, (10.0, 50.0), &Paint::default());
+        let picture = recorder.finish_recording_as_picture(None).unwrap();
+
+        // text blobs are compared by their unique id.
+        let ops = picture.ops();
+        assert_eq!(ops, picture.ops());
+        assert_ne!(ops[0].op, ops[1].op);
+        assert!(format!("{:?}", ops[0]).contains("DrawRect"));
+        assert!(format!("{:?}", ops[1]).contains(&format!("unique_id: {}", blob.unique_id())));
+    }
+}
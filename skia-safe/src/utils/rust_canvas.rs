@@ -0,0 +1,652 @@
+use crate::canvas::{PointMode, SrcRectConstraint};
+use crate::prelude::*;
+use crate::{
+    scalar, Canvas, ClipOp, IRect, ISize, Image, Matrix, OwnedCanvas, Paint, Path, Point, RRect,
+    Rect, Region, TextBlob,
+};
+use skia_bindings::{
+    C_RustCanvas_new, RustCanvas_Handler, RustCanvas_OtherDraw, SkCanvas_PointMode,
+    SkCanvas_SrcRectConstraint, SkClipOp, SkImage, SkMatrix, SkPaint, SkPath, SkPoint, SkRRect,
+    SkRect, SkRegion, SkTextBlob,
+};
+use std::any::Any;
+use std::cell::Cell;
+use std::ffi::c_void;
+use std::mem::ManuallyDrop;
+use std::ops::{Deref, DerefMut};
+use std::panic::{self, AssertUnwindSafe};
+use std::{ptr, slice, thread};
+
+/// Receives the calls made to a `RustCanvas`.
+///
+/// All functions default to doing nothing, so a handler only needs to implement the calls it
+/// is interested in. The canvas maintains its own matrix and clip state, so while a handler is
+/// notified about every change, it does not need to track them to query the canvas.
+///
+/// Draw calls that do not have their own function, like atlases, bitmaps, vertices, shadows and
+/// annotations, are reported to `on_draw_other()` without their arguments. Pictures and
+/// drawables are played back into the canvas, so the handler receives the calls they are made
+/// of.
+#[allow(unused_variables)]
+pub trait CanvasHandler {
+    fn on_save(&mut self) {}
+    fn on_save_layer(&mut self, bounds: Option<&Rect>, paint: Option<&Paint>) {}
+    fn on_restore(&mut self) {}
+
+    fn on_concat(&mut self, matrix: &Matrix) {}
+    fn on_set_matrix(&mut self, matrix: &Matrix) {}
+
+    fn on_clip_rect(&mut self, rect: &Rect, op: ClipOp, do_anti_alias: bool) {}
+    fn on_clip_rrect(&mut self, rrect: &RRect, op: ClipOp, do_anti_alias: bool) {}
+    fn on_clip_path(&mut self, path: &Path, op: ClipOp, do_anti_alias: bool) {}
+    fn on_clip_region(&mut self, region: &Region, op: ClipOp) {}
+
+    fn on_draw_paint(&mut self, paint: &Paint) {}
+    fn on_draw_points(&mut self, mode: PointMode, pts: &[Point], paint: &Paint) {}
+    fn on_draw_rect(&mut self, rect: &Rect, paint: &Paint) {}
+    fn on_draw_rrect(&mut self, rrect: &RRect, paint: &Paint) {}
+    fn on_draw_drrect(&mut self, outer: &RRect, inner: &RRect, paint: &Paint) {}
+    fn on_draw_oval(&mut self, oval: &Rect, paint: &Paint) {}
+    fn on_draw_arc(
+        &mut self,
+        oval: &Rect,
+        start_angle: scalar,
+        sweep_angle: scalar,
+        use_center: bool,
+        paint: &Paint,
+    ) {
+    }
+    fn on_draw_path(&mut self, path: &Path, paint: &Paint) {}
+    fn on_draw_region(&mut self, region: &Region, paint: &Paint) {}
+    fn on_draw_text_blob(&mut self, blob: &TextBlob, origin: Point, paint: &Paint) {}
+    fn on_draw_image(&mut self, image: &Image, left_top: Point, paint: Option<&Paint>) {}
+    fn on_draw_image_rect(
+        &mut self,
+        image: &Image,
+        src: Option<&Rect>,
+        dst: &Rect,
+        paint: Option<&Paint>,
+        constraint: SrcRectConstraint,
+    ) {
+    }
+    fn on_draw_other(&mut self, draw: OtherDraw) {}
+}
+
+/// The draw calls that are reported to `CanvasHandler::on_draw_other()`.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[repr(i32)]
+pub enum OtherDraw {
+    Annotation = RustCanvas_OtherDraw::Annotation as _,
+    Patch = RustCanvas_OtherDraw::Patch as _,
+    ImageNine = RustCanvas_OtherDraw::ImageNine as _,
+    ImageLattice = RustCanvas_OtherDraw::ImageLattice as _,
+    Bitmap = RustCanvas_OtherDraw::Bitmap as _,
+    BitmapRect = RustCanvas_OtherDraw::BitmapRect as _,
+    BitmapNine = RustCanvas_OtherDraw::BitmapNine as _,
+    BitmapLattice = RustCanvas_OtherDraw::BitmapLattice as _,
+    Vertices = RustCanvas_OtherDraw::Vertices as _,
+    Atlas = RustCanvas_OtherDraw::Atlas as _,
+    ShadowRec = RustCanvas_OtherDraw::ShadowRec as _,
+    EdgeAAQuad = RustCanvas_OtherDraw::EdgeAAQuad as _,
+    EdgeAAImageSet = RustCanvas_OtherDraw::EdgeAAImageSet as _,
+}
+
+impl NativeTransmutable<RustCanvas_OtherDraw> for OtherDraw {}
+#[test]
+fn test_other_draw_layout() {
+    OtherDraw::test_layout()
+}
+
+/// A canvas that forwards all its calls to a `CanvasHandler`.
+///
+/// `RustCanvas` dereferences to `Canvas`, so it can be used wherever a `Canvas` is expected,
+/// for example to play back a `Picture` or to render an SVG or Skottie animation.
+///
+/// Panics of the handler can not unwind through the native canvas. They are caught, the
+/// remaining calls are not forwarded anymore, and the panic is resumed as soon as the canvas or
+/// the handler is accessed again, or the `RustCanvas` is dropped.
+pub struct RustCanvas<H: CanvasHandler> {
+    // declared before the state, because the native canvas refers to it.
+    canvas: OwnedCanvas<'static>,
+    state: Box<State<H>>,
+}
+
+struct State<H> {
+    handler: H,
+    panic: Cell<Option<Box<dyn Any + Send>>>,
+}
+
+impl<H: CanvasHandler> Drop for RustCanvas<H> {
+    fn drop(&mut self) {
+        if !thread::panicking() {
+            self.resume_panic()
+        }
+    }
+}
+
+impl<H: CanvasHandler> Deref for RustCanvas<H> {
+    type Target = Canvas;
+
+    fn deref(&self) -> &Self::Target {
+        self.resume_panic();
+        &self.canvas
+    }
+}
+
+impl<H: CanvasHandler> DerefMut for RustCanvas<H> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.resume_panic();
+        &mut self.canvas
+    }
+}
+
+impl<H: CanvasHandler> AsMut<Canvas> for RustCanvas<H> {
+    fn as_mut(&mut self) -> &mut Canvas {
+        self.deref_mut()
+    }
+}
+
+impl<H: CanvasHandler> RustCanvas<H> {
+    pub fn new(size: impl Into<ISize>, handler: H) -> RustCanvas<H> {
+        Self::from_bounds(IRect::from_size(size), handler)
+    }
+
+    /// Creates a canvas that clips to `bounds` instead of starting at the origin.
+    pub fn from_bounds(bounds: impl AsRef<IRect>, handler: H) -> RustCanvas<H> {
+        let mut state = Box::new(State {
+            handler,
+            panic: Cell::new(None),
+        });
+        let native_handler = RustCanvas_Handler {
+            data: state.as_mut() as *mut State<H> as _,
+            save: Some(save::<H>),
+            saveLayer: Some(save_layer::<H>),
+            restore: Some(restore::<H>),
+            concat: Some(concat::<H>),
+            setMatrix: Some(set_matrix::<H>),
+            clipRect: Some(clip_rect::<H>),
+            clipRRect: Some(clip_rrect::<H>),
+            clipPath: Some(clip_path::<H>),
+            clipRegion: Some(clip_region::<H>),
+            drawPaint: Some(draw_paint::<H>),
+            drawPoints: Some(draw_points::<H>),
+            drawRect: Some(draw_rect::<H>),
+            drawRRect: Some(draw_rrect::<H>),
+            drawDRRect: Some(draw_drrect::<H>),
+            drawOval: Some(draw_oval::<H>),
+            drawArc: Some(draw_arc::<H>),
+            drawPath: Some(draw_path::<H>),
+            drawRegion: Some(draw_region::<H>),
+            drawTextBlob: Some(draw_text_blob::<H>),
+            drawImage: Some(draw_image::<H>),
+            drawImageRect: Some(draw_image_rect::<H>),
+            drawOther: Some(draw_other::<H>),
+        };
+        let canvas = Canvas::own_from_native_ptr(unsafe {
+            C_RustCanvas_new(bounds.as_ref().native(), &native_handler)
+        })
+        .unwrap();
+        RustCanvas { canvas, state }
+    }
+
+    pub fn handler(&self) -> &H {
+        self.resume_panic();
+        &self.state.handler
+    }
+
+    pub fn handler_mut(&mut self) -> &mut H {
+        self.resume_panic();
+        &mut self.state.handler
+    }
+
+    /// Deletes the canvas and returns the handler.
+    pub fn into_handler(self) -> H {
+        self.resume_panic();
+        // the canvas must be deleted before the state is moved out.
+        let mut this = ManuallyDrop::new(self);
+        unsafe {
+            ptr::drop_in_place(&mut this.canvas);
+            ptr::read(&this.state).handler
+        }
+    }
+
+    fn resume_panic(&self) {
+        if let Some(panic) = self.state.panic.take() {
+            panic::resume_unwind(panic)
+        }
+    }
+}
+
+unsafe fn forward<H>(data: *mut c_void, call: impl FnOnce(&mut H)) {
+    let state = &mut *(data as *mut State<H>);
+    // after a panic, the handler may be in an inconsistent state, so the remaining calls are
+    // dropped until the panic is resumed.
+    if state.panic.get_mut().is_some() {
+        return;
+    }
+    let handler = &mut state.handler;
+    if let Err(panic) = panic::catch_unwind(AssertUnwindSafe(|| call(handler))) {
+        *state.panic.get_mut() = Some(panic);
+    }
+}
+
+unsafe extern "C" fn save<H: CanvasHandler>(data: *mut c_void) {
+    forward::<H>(data, |handler| handler.on_save())
+}
+
+unsafe extern "C" fn save_layer<H: CanvasHandler>(
+    data: *mut c_void,
+    bounds: *const SkRect,
+    paint: *const SkPaint,
+) {
+    forward::<H>(data, |handler| {
+        handler.on_save_layer(
+            bounds.as_ref().map(Rect::from_native_ref),
+            paint.as_ref().map(Paint::from_native_ref),
+        )
+    })
+}
+
+unsafe extern "C" fn restore<H: CanvasHandler>(data: *mut c_void) {
+    forward::<H>(data, |handler| handler.on_restore())
+}
+
+unsafe extern "C" fn concat<H: CanvasHandler>(data: *mut c_void, matrix: *const SkMatrix) {
+    forward::<H>(data, |handler| {
+        handler.on_concat(Matrix::from_native_ref(&*matrix))
+    })
+}
+
+unsafe extern "C" fn set_matrix<H: CanvasHandler>(data: *mut c_void, matrix: *const SkMatrix) {
+    forward::<H>(data, |handler| {
+        handler.on_set_matrix(Matrix::from_native_ref(&*matrix))
+    })
+}
+
+unsafe extern "C" fn clip_rect<H: CanvasHandler>(
+    data: *mut c_void,
+    rect: *const SkRect,
+    op: SkClipOp,
+    do_anti_alias: bool,
+) {
+    forward::<H>(data, |handler| {
+        handler.on_clip_rect(
+            Rect::from_native_ref(&*rect),
+            ClipOp::from_native(op),
+            do_anti_alias,
+        )
+    })
+}
+
+unsafe extern "C" fn clip_rrect<H: CanvasHandler>(
+    data: *mut c_void,
+    rrect: *const SkRRect,
+    op: SkClipOp,
+    do_anti_alias: bool,
+) {
+    forward::<H>(data, |handler| {
+        handler.on_clip_rrect(
+            RRect::from_native_ref(&*rrect),
+            ClipOp::from_native(op),
+            do_anti_alias,
+        )
+    })
+}
+
+unsafe extern "C" fn clip_path<H: CanvasHandler>(
+    data: *mut c_void,
+    path: *const SkPath,
+    op: SkClipOp,
+    do_anti_alias: bool,
+) {
+    forward::<H>(data, |handler| {
+        handler.on_clip_path(
+            Path::from_native_ref(&*path),
+            ClipOp::from_native(op),
+            do_anti_alias,
+        )
+    })
+}
+
+unsafe extern "C" fn clip_region<H: CanvasHandler>(
+    data: *mut c_void,
+    region: *const SkRegion,
+    op: SkClipOp,
+) {
+    forward::<H>(data, |handler| {
+        handler.on_clip_region(Region::from_native_ref(&*region), ClipOp::from_native(op))
+    })
+}
+
+unsafe extern "C" fn draw_paint<H: CanvasHandler>(data: *mut c_void, paint: *const SkPaint) {
+    forward::<H>(data, |handler| {
+        handler.on_draw_paint(Paint::from_native_ref(&*paint))
+    })
+}
+
+unsafe extern "C" fn draw_points<H: CanvasHandler>(
+    data: *mut c_void,
+    mode: SkCanvas_PointMode,
+    count: usize,
+    pts: *const SkPoint,
+    paint: *const SkPaint,
+) {
+    forward::<H>(data, |handler| {
+        let pts = if count > 0 {
+            slice::from_raw_parts(pts as *const Point, count)
+        } else {
+            &[]
+        };
+        handler.on_draw_points(
+            PointMode::from_native(mode),
+            pts,
+            Paint::from_native_ref(&*paint),
+        )
+    })
+}
+
+unsafe extern "C" fn draw_rect<H: CanvasHandler>(
+    data: *mut c_void,
+    rect: *const SkRect,
+    paint: *const SkPaint,
+) {
+    forward::<H>(data, |handler| {
+        handler.on_draw_rect(
+            Rect::from_native_ref(&*rect),
+            Paint::from_native_ref(&*paint),
+        )
+    })
+}
+
+unsafe extern "C" fn draw_rrect<H: CanvasHandler>(
+    data: *mut c_void,
+    rrect: *const SkRRect,
+    paint: *const SkPaint,
+) {
+    forward::<H>(data, |handler| {
+        handler.on_draw_rrect(
+            RRect::from_native_ref(&*rrect),
+            Paint::from_native_ref(&*paint),
+        )
+    })
+}
+
+unsafe extern "C" fn draw_drrect<H: CanvasHandler>(
+    data: *mut c_void,
+    outer: *const SkRRect,
+    inner: *const SkRRect,
+    paint: *const SkPaint,
+) {
+    forward::<H>(data, |handler| {
+        handler.on_draw_drrect(
+            RRect::from_native_ref(&*outer),
+            RRect::from_native_ref(&*inner),
+            Paint::from_native_ref(&*paint),
+        )
+    })
+}
+
+unsafe extern "C" fn draw_oval<H: CanvasHandler>(
+    data: *mut c_void,
+    oval: *const SkRect,
+    paint: *const SkPaint,
+) {
+    forward::<H>(data, |handler| {
+        handler.on_draw_oval(
+            Rect::from_native_ref(&*oval),
+            Paint::from_native_ref(&*paint),
+        )
+    })
+}
+
+unsafe extern "C" fn draw_arc<H: CanvasHandler>(
+    data: *mut c_void,
+    oval: *const SkRect,
+    start_angle: scalar,
+    sweep_angle: scalar,
+    use_center: bool,
+    paint: *const SkPaint,
+) {
+    forward::<H>(data, |handler| {
+        handler.on_draw_arc(
+            Rect::from_native_ref(&*oval),
+            start_angle,
+            sweep_angle,
+            use_center,
+            Paint::from_native_ref(&*paint),
+        )
+    })
+}
+
+unsafe extern "C" fn draw_path<H: CanvasHandler>(
+    data: *mut c_void,
+    path: *const SkPath,
+    paint: *const SkPaint,
+) {
+    forward::<H>(data, |handler| {
+        handler.on_draw_path(
+            Path::from_native_ref(&*path),
+            Paint::from_native_ref(&*paint),
+        )
+    })
+}
+
+unsafe extern "C" fn draw_region<H: CanvasHandler>(
+    data: *mut c_void,
+    region: *const SkRegion,
+    paint: *const SkPaint,
+) {
+    forward::<H>(data, |handler| {
+        handler.on_draw_region(
+            Region::from_native_ref(&*region),
+            Paint::from_native_ref(&*paint),
+        )
+    })
+}
+
+unsafe extern "C" fn draw_text_blob<H: CanvasHandler>(
+    data: *mut c_void,
+    blob: *const SkTextBlob,
+    x: scalar,
+    y: scalar,
+    paint: *const SkPaint,
+) {
+    forward::<H>(data, |handler| {
+        let blob = TextBlob::from_unshared_ptr(blob as *mut SkTextBlob).unwrap();
+        handler.on_draw_text_blob(&blob, Point::new(x, y), Paint::from_native_ref(&*paint))
+    })
+}
+
+unsafe extern "C" fn draw_image<H: CanvasHandler>(
+    data: *mut c_void,
+    image: *const SkImage,
+    left: scalar,
+    top: scalar,
+    paint: *const SkPaint,
+) {
+    forward::<H>(data, |handler| {
+        let image = Image::from_unshared_ptr(image as *mut SkImage).unwrap();
+        handler.on_draw_image(
+            &image,
+            Point::new(left, top),
+            paint.as_ref().map(Paint::from_native_ref),
+        )
+    })
+}
+
+unsafe extern "C" fn draw_image_rect<H: CanvasHandler>(
+    data: *mut c_void,
+    image: *const SkImage,
+    src: *const SkRect,
+    dst: *const SkRect,
+    paint: *const SkPaint,
+    constraint: SkCanvas_SrcRectConstraint,
+) {
+    forward::<H>(data, |handler| {
+        let image = Image::from_unshared_ptr(image as *mut SkImage).unwrap();
+        handler.on_draw_image_rect(
+            &image,
+            src.as_ref().map(Rect::from_native_ref),
+            Rect::from_native_ref(&*dst),
+            paint.as_ref().map(Paint::from_native_ref),
+            SrcRectConstraint::from_native(constraint),
+        )
+    })
+}
+
+unsafe extern "C" fn draw_other<H: CanvasHandler>(data: *mut c_void, draw: RustCanvas_OtherDraw) {
+    forward::<H>(data, |handler| {
+        handler.on_draw_other(OtherDraw::from_native(draw))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CanvasHandler, OtherDraw, RustCanvas};
+    use crate::{
+        ClipOp, Color, Data, Font, Matrix, Paint, Path, PictureRecorder, Point, Rect, TextBlob,
+        Typeface,
+    };
+    use std::panic::{self, AssertUnwindSafe};
+
+    #[derive(Default)]
+    struct Logger(Vec<String>);
+
+    impl CanvasHandler for Logger {
+        fn on_save(&mut self) {
+            self.0.push("save".into())
+        }
+
+        fn on_restore(&mut self) {
+            self.0.push("restore".into())
+        }
+
+        fn on_concat(&mut self, matrix: &Matrix) {
+            self.0.push(format!(
+                "concat {} {}",
+                matrix.translate_x(),
+                matrix.translate_y()
+            ))
+        }
+
+        fn on_clip_rect(&mut self, rect: &Rect, op: ClipOp, do_anti_alias: bool) {
+            self.0.push(format!(
+                "clip_rect {} {:?} {}",
+                rect.width(),
+                op,
+                do_anti_alias
+            ))
+        }
+
+        fn on_draw_rect(&mut self, rect: &Rect, _paint: &Paint) {
+            self.0.push(format!("draw_rect {}", rect.width()))
+        }
+
+        fn on_draw_path(&mut self, path: &Path, _paint: &Paint) {
+            self.0.push(format!("draw_path {}", path.count_points()))
+        }
+
+        fn on_draw_text_blob(&mut self, _blob: &TextBlob, origin: Point, _paint: &Paint) {
+            self.0
+                .push(format!("draw_text_blob {} {}", origin.x, origin.y))
+        }
+
+        fn on_draw_other(&mut self, draw: OtherDraw) {
+            self.0.push(format!("draw_other {:?}", draw))
+        }
+    }
+
+    #[test]
+    #[allow(clippy::float_cmp)]
+    fn calls_are_forwarded_to_the_handler() {
+        let mut canvas = RustCanvas::new((100, 100), Logger::default());
+        let paint = Paint::default();
+        canvas.save();
+        canvas.translate((10.0, 20.0));
+        canvas.clip_rect(Rect::from_wh(50.0, 50.0), ClipOp::Intersect, true);
+        canvas.draw_rect(Rect::from_wh(5.0, 5.0), &paint);
+        let mut path = Path::default();
+        path.move_to((0.0, 0.0)).line_to((1.0, 1.0));
+        canvas.draw_path(&path, &paint);
+        let font = Font::from_typeface(&Typeface::default(), 12.0);
+        let blob = TextBlob::from_str("Hi", &font).unwrap();
+        canvas.draw_text_blob(&blob, (1.0, 2.0), &paint);
+        // the canvas keeps track of the matrix.
+        assert_eq!(canvas.total_matrix().translate_x(), 10.0);
+        canvas.restore();
+
+        assert_eq!(
+            canvas.into_handler().0,
+            [
+                "save",
+                "concat 10 20",
+                "clip_rect 50 Intersect true",
+                "draw_rect 5",
+                "draw_path 2",
+                "draw_text_blob 1 2",
+                "restore"
+            ]
+        );
+    }
+
+    #[test]
+    fn pictures_are_played_back_into_the_handler() {
+        let mut recorder = PictureRecorder::new();
+        let bounds = Rect::from_wh(100.0, 100.0);
+        let recording_canvas = recorder.begin_recording(&bounds, None, None);
+        recording_canvas.draw_rect(Rect::from_wh(10.0, 10.0), &Paint::default());
+        let picture = recorder.finish_recording_as_picture(None).unwrap();
+
+        let mut canvas = RustCanvas::new((100, 100), Logger::default());
+        picture.playback(&mut canvas);
+        assert!(canvas.handler().0.contains(&"draw_rect 10".to_string()));
+    }
+
+    #[test]
+    fn other_draws_are_reported() {
+        let mut canvas = RustCanvas::new((100, 100), Logger::default());
+        canvas.draw_annotation(Rect::from_wh(10.0, 10.0), "key", &Data::new_copy(b"value"));
+        assert_eq!(canvas.handler().0, ["draw_other Annotation"]);
+    }
+
+    #[test]
+    fn shadows_are_reported() {
+        let mut canvas = RustCanvas::new((100, 100), Logger::default());
+        let mut path = Path::default();
+        path.add_rect(Rect::new(10.0, 10.0, 50.0, 50.0), None);
+        canvas.draw_shadow(
+            &path,
+            (0.0, 0.0, 4.0),
+            (50.0, 0.0, 100.0),
+            20.0,
+            Color::from_argb(0x40, 0, 0, 0),
+            Color::from_argb(0x80, 0, 0, 0),
+            None,
+        );
+        assert_eq!(canvas.handler().0, ["draw_other ShadowRec"]);
+    }
+
+    #[test]
+    fn handler_panics_are_resumed_after_the_call() {
+        struct Panicking(usize);
+
+        impl CanvasHandler for Panicking {
+            fn on_draw_rect(&mut self, _rect: &Rect, _paint: &Paint) {
+                self.0 += 1;
+                panic!("draw_rect");
+            }
+        }
+
+        let mut canvas = RustCanvas::new((100, 100), Panicking(0));
+        let paint = Paint::default();
+        canvas.canvas.draw_rect(Rect::from_wh(5.0, 5.0), &paint);
+        // calls after the panic are not forwarded.
+        canvas.canvas.draw_rect(Rect::from_wh(5.0, 5.0), &paint);
+        let resumed = panic::catch_unwind(AssertUnwindSafe(|| canvas.handler().0));
+        assert_eq!(
+            *resumed.unwrap_err().downcast::<&str>().unwrap(),
+            "draw_rect"
+        );
+        assert_eq!(canvas.into_handler().0, 1);
+    }
+}
@@ -1,5 +1,8 @@
 use crate::prelude::*;
-use crate::{Canvas, Font, Paint, Path, Point, TextEncoding};
+use crate::{
+    scalar, Canvas, ContourMeasure, ContourMeasureIter, Font, Paint, Path, Point, RSXform,
+    TextBlob, TextBlobBuilder, TextEncoding,
+};
 use core::borrow::BorrowMut;
 use skia_bindings::{SkTextUtils, SkTextUtils_Align};
 
@@ -79,3 +82,115 @@ impl Path {
         get_path(text, p, font)
     }
 }
+
+// rust specific, lays out the glyphs along the contours of a path.
+
+/// Creates a text blob with the glyphs of `text` placed along `path`. Each glyph is rotated
+/// to the tangent of the path at the glyph's horizontal center.
+///
+/// `offset` is the distance along the path at which the text starts, after it has been
+/// aligned to the path's length with `align`. The contours of the path are treated as
+/// one continuous path. Glyphs that fall outside of the path are dropped.
+///
+/// Returns `None` if no glyph is placed on the path.
+pub fn text_blob_on_path(
+    text: impl AsRef<str>,
+    font: &Font,
+    path: &Path,
+    offset: scalar,
+    align: Align,
+) -> Option<TextBlob> {
+    let glyphs = font.str_to_glyphs_vec(text);
+    let mut widths = vec![0.0; glyphs.len()];
+    font.get_widths(&glyphs, &mut widths);
+
+    let contours: Vec<ContourMeasure> = ContourMeasureIter::from_path(path, false, None).collect();
+    let path_length: scalar = contours.iter().map(|c| c.length()).sum();
+    let text_length: scalar = widths.iter().sum();
+    let mut distance = offset
+        + match align {
+            Align::Left => 0.0,
+            Align::Center => (path_length - text_length) / 2.0,
+            Align::Right => path_length - text_length,
+        };
+
+    let mut placed = Vec::with_capacity(glyphs.len());
+    for (glyph, width) in glyphs.iter().zip(widths.iter()) {
+        let half_width = width / 2.0;
+        if let Some((p, tangent)) = pos_tan(&contours, distance + half_width) {
+            let xform = RSXform::new(
+                tangent.x,
+                tangent.y,
+                (p.x - tangent.x * half_width, p.y - tangent.y * half_width),
+            );
+            placed.push((*glyph, xform));
+        }
+        distance += width;
+    }
+
+    if placed.is_empty() {
+        return None;
+    }
+
+    let mut builder = TextBlobBuilder::new();
+    let (glyphs, xforms) = builder.alloc_run_rsxform(font, placed.len());
+    for (i, (glyph, xform)) in placed.into_iter().enumerate() {
+        glyphs[i] = glyph;
+        xforms[i] = xform;
+    }
+    builder.make()
+}
+
+/// The position and tangent at `distance` along all `contours`.
+fn pos_tan(contours: &[ContourMeasure], mut distance: scalar) -> Option<(Point, Point)> {
+    if distance < 0.0 {
+        return None;
+    }
+    for contour in contours {
+        let length = contour.length();
+        if distance <= length {
+            return contour.pos_tan(distance);
+        }
+        distance -= length;
+    }
+    None
+}
+
+impl TextBlob {
+    pub fn from_str_on_path(
+        text: impl AsRef<str>,
+        font: &Font,
+        path: &Path,
+        offset: scalar,
+        align: Align,
+    ) -> Option<TextBlob> {
+        text_blob_on_path(text, font, path, offset, align)
+    }
+}
+
+#[test]
+fn text_on_a_straight_path_follows_the_path() {
+    use crate::{TextBlobRunPositions, Typeface};
+
+    let font = Font::from_typeface(&Typeface::default(), 12.0);
+    let mut path = Path::default();
+    path.move_to((0.0, 0.0)).line_to((0.0, 1000.0));
+
+    let blob = text_blob_on_path("abc", &font, &path, 10.0, Align::Left).unwrap();
+    let run = blob.runs().next().unwrap();
+    assert_eq!(run.glyphs.len(), 3);
+    match run.positions {
+        TextBlobRunPositions::RSXform(xforms) => {
+            // the path points downwards, so the glyphs are rotated by 90 degrees.
+            assert!(xforms
+                .iter()
+                .all(|x| x.scos.abs() < 1e-5 && (x.ssin - 1.0).abs() < 1e-5));
+            assert!((xforms[0].ty - 10.0).abs() < 1e-3);
+            assert!(xforms[1].ty > xforms[0].ty);
+        }
+        _ => panic!("expected RSXform positioning"),
+    }
+
+    // glyphs beyond the end of the path are dropped.
+    assert!(text_blob_on_path("abc", &font, &path, 2000.0, Align::Left).is_none());
+}